@@ -0,0 +1,98 @@
+//! 管理后台的列表描述
+//!
+//! `--emit admin` 时为每张表生成描述常量（admin_views.rs）：表的显示名称、列的标签，
+//! 以及根据索引判断的可搜索、可排序字段，供管理后台按描述生成列表页。
+
+use std::collections::HashMap;
+
+use heck::ToShoutySnakeCase;
+use serde::Serialize;
+
+use crate::{sql, Column, Generator, Table};
+
+/// 单张表的描述
+#[derive(Debug, Clone, Serialize)]
+pub struct TableDescriptor {
+    /// 常量名称
+    pub const_name: String,
+    /// 表名的字符串字面量
+    pub table: String,
+    /// 显示名称的字符串字面量，取表注释的第一行，没有注释时为表名
+    pub display_name: String,
+    /// 表所属的 feature
+    pub feature: Option<String>,
+    pub columns: Vec<ColumnDescriptor>,
+}
+
+/// 单列的描述，字符串均为已包含引号和转义的字面量
+#[derive(Debug, Clone, Serialize)]
+pub struct ColumnDescriptor {
+    /// 列名
+    pub name: String,
+    /// 结构体中的字段名
+    pub field: String,
+    /// 标签，取列注释的第一行，没有注释时为字段名
+    pub label: String,
+    /// 可以作为查询条件：带索引且支持等值比较
+    pub searchable: bool,
+    /// 可以排序：带索引
+    pub sortable: bool,
+    pub nullable: bool,
+}
+
+impl ColumnDescriptor {
+    fn new(column: &Column) -> Self {
+        let field = column
+            .name
+            .as_deref()
+            .unwrap_or_default()
+            .trim_start_matches("r#");
+        let indexed = column.is_indexed || column.is_primary_key;
+        let comparable = !column.field_type.starts_with("Vec<")
+            && !column.field_type.starts_with("sqlx_postgres")
+            && column.field_type != "serde_json::Value";
+        Self {
+            name: format!("{:?}", sql::raw_name(column)),
+            field: format!("{field:?}"),
+            label: format!("{:?}", first_line(column.comment.as_deref(), field)),
+            searchable: indexed && comparable,
+            sortable: indexed && comparable,
+            nullable: column.is_nullable,
+        }
+    }
+}
+
+/// 注释的第一行，注释为空时使用 default
+fn first_line<'a>(comment: Option<&'a str>, default: &'a str) -> &'a str {
+    comment
+        .and_then(|c| c.lines().map(str::trim).find(|l| !l.is_empty()))
+        .unwrap_or(default)
+}
+
+/// 按 mod.rs 中模块的顺序收集每张表的描述
+pub(crate) fn collect(
+    generator: &Generator,
+    table_map: &HashMap<String, Table>,
+    tables_columns: &[Column],
+) -> Vec<TableDescriptor> {
+    generator
+        .module_sections(table_map)
+        .iter()
+        .flat_map(|s| s.tables.iter())
+        .map(|table_name| {
+            let table = &table_map[table_name];
+            let mut columns = tables_columns
+                .iter()
+                .filter(|c| c.table_name.as_ref() == Some(table_name))
+                .collect::<Vec<_>>();
+            columns.sort_by_key(|c| c.ordinal);
+            TableDescriptor {
+                const_name: table_name.to_shouty_snake_case(),
+                table: format!("{table_name:?}"),
+                display_name: format!("{:?}", first_line(Some(&table.comment), table_name)),
+                feature: generator.table_feature(table_name).map(str::to_string),
+                columns: columns.into_iter().map(ColumnDescriptor::new).collect(),
+            }
+        })
+        .collect()
+}
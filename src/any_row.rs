@@ -0,0 +1,93 @@
+//! 通过 sqlx::any 解码
+//!
+//! `--any-row` 时为模型生成 `TryFrom<&sqlx::any::AnyRow>`（`#[cfg(feature = "any")]`），用于运行时才确定数据库的应用。
+//! Any 只支持 bool、i16、i32、i64、f32、f64、String、Vec<u8>，其他类型先解码为 i64 或 String 再转换；
+//! 含有无法转换的列（数组、PostgreSQL 特有的类型等）的表不生成。
+
+use serde::Serialize;
+
+use crate::Column;
+
+/// 单个字段的解码方式
+#[derive(Serialize, Debug, Clone)]
+pub struct AnyField {
+    /// 字段名
+    pub field: String,
+    /// 列名
+    pub column: String,
+    pub nullable: bool,
+    /// 先解码为的类型，Any 直接支持时为空
+    pub via: Option<&'static str>,
+    /// 从 v 转换为字段类型的表达式，返回 Result
+    pub convert: Option<String>,
+}
+
+impl AnyField {
+    /// Any 无法解码的类型返回 None
+    pub fn new(column: &Column) -> Option<Self> {
+        let field_type = column.field_type.as_str();
+        let (via, convert) = match field_type {
+            "bool" | "i16" | "i32" | "i64" | "f32" | "f64" | "String" | "Vec<u8>" | "CiString" => {
+                (None, None)
+            }
+            "i8" | "u8" | "u16" | "u32" | "u64" => {
+                (Some("i64"), Some(format!("{field_type}::try_from(v)")))
+            }
+            "uuid::Uuid" => (
+                Some("String"),
+                Some("uuid::Uuid::parse_str(&v)".to_string()),
+            ),
+            "bigdecimal::BigDecimal" | "rust_decimal::Decimal" | "std::net::IpAddr" => {
+                (Some("String"), Some(format!("v.parse::<{field_type}>()")))
+            }
+            "serde_json::Value" => (
+                Some("String"),
+                Some("serde_json::from_str::<serde_json::Value>(&v)".to_string()),
+            ),
+            "time::Date" => (
+                Some("String"),
+                Some("super::any_decode::date(&v)".to_string()),
+            ),
+            "time::Time" => (
+                Some("String"),
+                Some("super::any_decode::time(&v)".to_string()),
+            ),
+            "time::PrimitiveDateTime" => (
+                Some("String"),
+                Some("super::any_decode::primitive_date_time(&v)".to_string()),
+            ),
+            "time::OffsetDateTime" => (
+                Some("String"),
+                Some("super::any_decode::offset_date_time(&v)".to_string()),
+            ),
+            "UtcDateTime" => (
+                Some("String"),
+                Some("super::any_decode::primitive_date_time(&v).map(UtcDateTime)".to_string()),
+            ),
+            // 枚举实现了 FromStr
+            _ if !column.enum_values.is_empty() => {
+                (Some("String"), Some(format!("v.parse::<{field_type}>()")))
+            }
+            _ => return None,
+        };
+        Some(Self {
+            field: column.name.clone().unwrap_or_default(),
+            column: crate::sql::raw_name(column).to_string(),
+            nullable: column.is_nullable,
+            via,
+            convert,
+        })
+    }
+}
+
+/// 表的全部字段，有 Any 无法解码的列时返回该列
+pub fn fields<'a>(columns: &[&'a Column]) -> Result<Vec<AnyField>, &'a Column> {
+    columns.iter().map(|c| AnyField::new(c).ok_or(*c)).collect()
+}
+
+/// 是否需要 any_decode 中解析时间的函数
+pub fn needs_time(columns: &[Column]) -> bool {
+    columns
+        .iter()
+        .any(|c| c.field_type.starts_with("time::") || c.field_type == "UtcDateTime")
+}
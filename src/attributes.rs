@@ -0,0 +1,37 @@
+//! 字段属性
+//!
+//! 个别字段需要的 serde、validator 等属性可以在配置文件中指定，原样加到生成的字段上，不需要修改模板。
+//!
+//! ```toml
+//! users.email = { serde = ["skip_serializing_if = \"Option::is_none\""], validate = ["email"] }
+//! users.nick_name = { attrs = ["schemars(length(max = 20))"] }
+//! ```
+
+use std::{collections::HashMap, fs};
+
+use serde::{Deserialize, Serialize};
+
+/// 字段上的属性，内容不含 #[...]
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct FieldAttributes {
+    /// 生成 #[serde(...)]
+    pub serde: Vec<String>,
+    /// 生成 #[validate(...)]
+    pub validate: Vec<String>,
+    /// 其他属性，如 schemars(...)，生成 #[...]
+    pub attrs: Vec<String>,
+}
+
+/// 属性配置，K：表名，V：K 为列名的字段属性
+#[derive(Deserialize, Default, Debug, Clone)]
+#[serde(transparent)]
+pub struct Attributes(pub HashMap<String, HashMap<String, FieldAttributes>>);
+
+impl Attributes {
+    /// 读取属性配置文件，作为 clap 的 value_parser 使用
+    pub fn load(path: &str) -> Result<Self, String> {
+        let contents = fs::read_to_string(path).map_err(|e| format!("读取 {path} 失败：{e}"))?;
+        toml::from_str(&contents).map_err(|e| format!("解析 {path} 失败：{e}"))
+    }
+}
@@ -0,0 +1,206 @@
+//! 读取表结构时的原始目录数据
+//!
+//! `--save-catalog` 将 information_schema（Sqlite 为 sqlite_master 和 pragma）的原始查询结果保存为 JSON，
+//! `--offline-catalog` 用保存的文件代替数据库作为数据源。保存的是转换为 Rust 类型之前的记录，
+//! 类型映射、命名转换和模板都与直接读取数据库时相同，没有客户数据库的访问权限时也能复现类型映射和模板的问题。
+//! 只保存表、列、枚举类型和库注释，统计信息、PostgreSQL 的约束和外键引用的其他库中的表不在其中。
+
+use std::{collections::HashMap, fs};
+
+use serde::{Deserialize, Serialize};
+use sqlx::Pool;
+
+use crate::{
+    chunk::{self, as_strs},
+    mysql, postgres, sqlite,
+    version::ServerVersion,
+    Column, Driver, Table,
+};
+
+/// 原始目录数据，driver 字段区分驱动
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "driver", rename_all = "lowercase")]
+pub(crate) enum Catalog {
+    Mysql {
+        database: String,
+        schema_comment: Option<String>,
+        tables: Vec<mysql::Table>,
+        columns: Vec<mysql::TableColumn>,
+    },
+    Postgres {
+        database: String,
+        schema_comment: Option<String>,
+        tables: Vec<postgres::Table>,
+        columns: Vec<postgres::TableColumn>,
+        /// K：类型名，V：枚举值
+        enum_types: HashMap<String, Vec<String>>,
+    },
+    Sqlite {
+        tables: Vec<sqlite::Table>,
+        columns: Vec<sqlite::TableInfo>,
+    },
+}
+
+/// 只读取驱动，用于未指定 --driver 时
+#[derive(Deserialize)]
+struct Header {
+    driver: Driver,
+    #[serde(default)]
+    database: String,
+}
+
+impl Catalog {
+    /// 指定的表名很多时分批读取，最多同时执行 concurrency 个查询
+    pub async fn read_mysql(
+        database: &str,
+        pool: &Pool<sqlx::MySql>,
+        table_names: &[&str],
+        version: &ServerVersion,
+        concurrency: usize,
+    ) -> anyhow::Result<Self> {
+        let names = chunk::owned(table_names);
+        let tables = chunk::chunked(&names, concurrency, |names| {
+            let (database, pool) = (database.to_string(), pool.clone());
+            async move { mysql::raw_tables(&database, &pool, &as_strs(&names)).await }
+        })
+        .await?;
+        let columns = chunk::chunked(&names, concurrency, |names| {
+            let (database, pool, version) = (database.to_string(), pool.clone(), *version);
+            async move { mysql::raw_columns(&database, &pool, &as_strs(&names), &version).await }
+        })
+        .await?;
+        Ok(Self::Mysql {
+            database: database.to_string(),
+            schema_comment: mysql::schema_comment(database, pool).await?,
+            tables,
+            columns,
+        })
+    }
+
+    pub async fn read_postgres(
+        database: &str,
+        pool: &Pool<sqlx::Postgres>,
+        table_names: &[&str],
+        version: &ServerVersion,
+        concurrency: usize,
+    ) -> anyhow::Result<Self> {
+        let names = chunk::owned(table_names);
+        let tables = chunk::chunked(&names, concurrency, |names| {
+            let (database, pool, version) = (database.to_string(), pool.clone(), *version);
+            async move {
+                postgres::raw_tables(&database, "public", &pool, &as_strs(&names), &version).await
+            }
+        })
+        .await?;
+        let columns = chunk::chunked(&names, concurrency, |names| {
+            let (database, pool) = (database.to_string(), pool.clone());
+            async move { postgres::raw_columns(&database, "public", &pool, &as_strs(&names)).await }
+        })
+        .await?;
+        Ok(Self::Postgres {
+            database: database.to_string(),
+            schema_comment: postgres::schema_comment(pool).await?,
+            tables,
+            columns,
+            enum_types: postgres::enum_types(pool).await?,
+        })
+    }
+
+    /// schemas 为 main 和 ATTACH 的库名，每张表的 pragma 分批并发执行
+    pub async fn read_sqlite(
+        pool: &Pool<sqlx::Sqlite>,
+        schemas: &[&str],
+        table_names: &[&str],
+        concurrency: usize,
+    ) -> anyhow::Result<Self> {
+        let (mut tables, mut columns) = (vec![], vec![]);
+        for schema in schemas {
+            let schema_tables = chunk::chunked(&chunk::owned(table_names), concurrency, |names| {
+                let (schema, pool) = (schema.to_string(), pool.clone());
+                async move { sqlite::raw_tables(&pool, &schema, &as_strs(&names)).await }
+            })
+            .await?;
+            // 未指定表名时读取全部表的列
+            let names = schema_tables
+                .iter()
+                .map(|t| t.name().to_string())
+                .collect::<Vec<_>>();
+            columns.extend(
+                chunk::chunked(&names, concurrency, |names| {
+                    let (schema, pool) = (schema.to_string(), pool.clone());
+                    async move { sqlite::raw_columns(&pool, &schema, &as_strs(&names)).await }
+                })
+                .await?,
+            );
+            tables.extend(schema_tables);
+        }
+        Ok(Self::Sqlite { tables, columns })
+    }
+
+    pub fn load(file: &str) -> anyhow::Result<Self> {
+        let value = fs::read_to_string(file)
+            .map_err(|e| anyhow::anyhow!("读取 {file} 失败：{e}"))?
+            .parse::<tera::Value>()
+            .map_err(|e| anyhow::anyhow!("{file} 不是有效的 JSON：{e}"))?;
+        tera::from_value(value).map_err(|e| anyhow::anyhow!("{file} 不是导出的目录数据：{e}"))
+    }
+
+    /// 文件中的驱动和库名，不读取表和列
+    pub fn header(file: &str) -> anyhow::Result<(Driver, String)> {
+        let value = fs::read_to_string(file)
+            .map_err(|e| anyhow::anyhow!("读取 {file} 失败：{e}"))?
+            .parse::<tera::Value>()
+            .map_err(|e| anyhow::anyhow!("{file} 不是有效的 JSON：{e}"))?;
+        let header: Header = tera::from_value(value)
+            .map_err(|e| anyhow::anyhow!("{file} 不是导出的目录数据：{e}"))?;
+        Ok((header.driver, header.database))
+    }
+
+    pub fn save(&self, file: &str) -> anyhow::Result<()> {
+        fs::write(file, format!("{:#}\n", tera::to_value(self)?))
+            .map_err(|e| anyhow::anyhow!("写入 {file} 失败：{e}"))
+    }
+
+    pub fn schema_comment(&self) -> Option<String> {
+        match self {
+            Self::Mysql { schema_comment, .. } | Self::Postgres { schema_comment, .. } => {
+                schema_comment.clone()
+            }
+            Self::Sqlite { .. } => None,
+        }
+    }
+
+    /// 按与直接读取数据库时相同的规则转换为表和列，table_names 不为空时只保留这些表
+    pub fn into_schema(self, table_names: &[&str]) -> (Vec<Table>, Vec<Column>) {
+        let schema_comment = self.schema_comment();
+        let (mut tables, mut columns) = match self {
+            Self::Mysql {
+                tables, columns, ..
+            } => (
+                tables.into_iter().map(Table::from).collect::<Vec<_>>(),
+                columns.into_iter().map(Column::from).collect::<Vec<_>>(),
+            ),
+            Self::Postgres {
+                tables,
+                columns,
+                enum_types,
+                ..
+            } => (
+                tables.into_iter().map(Table::from).collect(),
+                postgres::convert_columns(columns, &enum_types),
+            ),
+            Self::Sqlite { tables, columns } => (
+                tables.into_iter().map(Table::from).collect(),
+                sqlite::convert_columns(columns),
+            ),
+        };
+        if !table_names.is_empty() {
+            tables.retain(|t| table_names.contains(&t.name.as_str()));
+            columns.retain(|c| table_names.contains(&c.table_name.as_deref().unwrap_or_default()));
+        }
+        for table in tables.iter_mut() {
+            table.schema_comment = schema_comment.clone();
+        }
+        (tables, columns)
+    }
+}
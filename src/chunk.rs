@@ -0,0 +1,68 @@
+//! 分批、并发执行按表名过滤的查询
+//!
+//! 指定的表名（-t、外键引用的表）放在 IN 列表中查询，表名上万时语句会超过 MySQL 绑定参数个数（65535）
+//! 或 max_allowed_packet 的限制。表名超过一批时按 [`CHUNK_SIZE`] 分批查询，
+//! 同时执行的批数受 --introspect-concurrency 限制，前面的批次未完成时不再发出新的查询，
+//! 不会占满数据库的连接数。Sqlite 每张表的 pragma 同样分批并发执行。
+//!
+//! 未指定表名时仍然一次查询整个库：PostgreSQL 对 information_schema 加上表名列表后
+//! 执行计划变差，分批查询全部表比一次查询慢得多。
+
+use std::{future::Future, sync::Arc};
+
+use tokio::{sync::Semaphore, task::JoinSet};
+
+/// 每批查询的表名个数
+pub(crate) const CHUNK_SIZE: usize = 500;
+
+/// 按 CHUNK_SIZE 分批查询 names，最多同时执行 concurrency 批，结果按批次的顺序拼接；
+/// names 不超过一批（包括为空，即查询全部表）时直接查询
+pub(crate) async fn chunked<N, T, F, Fut>(
+    names: &[N],
+    concurrency: usize,
+    query: F,
+) -> anyhow::Result<Vec<T>>
+where
+    N: Clone,
+    F: Fn(Vec<N>) -> Fut,
+    Fut: Future<Output = anyhow::Result<Vec<T>>> + Send + 'static,
+    T: Send + 'static,
+{
+    if names.len() <= CHUNK_SIZE {
+        return query(names.to_vec()).await;
+    }
+
+    let semaphore = Arc::new(Semaphore::new(concurrency.max(1)));
+    let mut set = JoinSet::new();
+    for (idx, chunk) in names.chunks(CHUNK_SIZE).enumerate() {
+        // 等待空闲的位置后再发出下一批
+        let permit = semaphore.clone().acquire_owned().await?;
+        let future = query(chunk.to_vec());
+        set.spawn(async move {
+            let result = future.await;
+            drop(permit);
+            (idx, result)
+        });
+    }
+
+    let mut results = vec![];
+    while let Some(result) = set.join_next().await {
+        results.push(result?);
+    }
+    results.sort_by_key(|(idx, _)| *idx);
+    let mut rows = vec![];
+    for (_, result) in results {
+        rows.extend(result?);
+    }
+    Ok(rows)
+}
+
+/// 传给 chunked 的表名，查询在单独的任务中执行，不能借用
+pub(crate) fn owned(names: &[&str]) -> Vec<String> {
+    names.iter().map(|n| n.to_string()).collect()
+}
+
+/// 表名转换为查询函数需要的 &str
+pub(crate) fn as_strs(names: &[String]) -> Vec<&str> {
+    names.iter().map(String::as_str).collect()
+}
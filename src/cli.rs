@@ -0,0 +1,132 @@
+//! 命令行入口
+//!
+//! 子命令：
+//! - `generate` 生成代码，不指定子命令时等同于 generate，兼容旧的用法
+//! - `check`    只比较生成结果与已有文件，不写入文件（generate --dry-run）
+//! - `diff`     比较数据库中的表结构与已生成的结构体，列出每张表新增、删除和类型变化的列
+//! - `inspect`  查看表结构：不指定表名时列出所有表，指定表名时输出该表的列
+//! - `init`     在当前目录生成配置文件模板
+//! - `completions` 输出 shell 的补全脚本
+//! - `serve`    启动 HTTP 服务，浏览表结构、预览和下载生成的代码（需要开启 serve 特性）
+//!
+//! 连接参数、驱动（`--driver`）等选项在各个子命令中相同，也都可以写在配置文件中。
+
+use std::io::{self, IsTerminal};
+
+use clap::{Args, CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
+
+use crate::{config::Init, Generator};
+
+/// sqlx-db-cli
+#[derive(Parser, Debug)]
+#[command(
+    author,
+    version,
+    about,
+    long_about = None,
+    args_conflicts_with_subcommands = true,
+    after_help = "不指定子命令时等同于 generate"
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    #[command(flatten)]
+    pub generator: Generator,
+}
+
+impl Cli {
+    /// 按 -v、-q 初始化日志，日志输出到标准错误，标准输出只保留汇总、依赖等结果
+    pub fn init_tracing(&self) {
+        let level = match &self.command {
+            None => self.generator.log_level(),
+            Some(
+                Command::Generate(generator) | Command::Check(generator) | Command::Diff(generator),
+            ) => generator.log_level(),
+            Some(Command::Inspect(inspect)) => inspect.generator.log_level(),
+            #[cfg(feature = "serve")]
+            Some(Command::Serve(serve)) => serve.generator.log_level(),
+            Some(Command::Init(_) | Command::Completions(_)) => tracing::Level::INFO,
+        };
+        tracing_subscriber::fmt()
+            .with_max_level(level)
+            .with_target(false)
+            .without_time()
+            .with_ansi(io::stderr().is_terminal())
+            .with_writer(io::stderr)
+            .init();
+    }
+}
+
+/// 子命令
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// 生成代码
+    Generate(Generator),
+    /// 只比较生成结果与已有文件，不写入文件，有变化时退出码为 1
+    Check(Generator),
+    /// 比较数据库中的表结构与已生成的结构体，列出每张表新增、删除和类型变化的列，有差异时退出码为 1
+    Diff(Generator),
+    /// 查看表结构：不指定表名时列出表名、行数和表注释，指定表名时输出该表的列及映射的 Rust 类型
+    #[command(alias = "list-tables", alias = "describe")]
+    Inspect(Inspect),
+    /// 在当前目录生成配置文件模板
+    Init(Init),
+    /// 输出 shell 的补全脚本，如：sqlx-db-cli completions bash > /etc/bash_completion.d/sqlx-db-cli
+    Completions(Completions),
+    /// 启动 HTTP 服务，浏览表结构、预览和下载生成的代码，每次请求都会重新读取表结构，不写入输出目录
+    #[cfg(feature = "serve")]
+    Serve(Serve),
+}
+
+/// inspect 子命令的参数
+#[derive(Args, Debug)]
+pub struct Inspect {
+    /// 表名
+    pub table: Option<String>,
+    /// 输出 JSON（只用于列出表）
+    #[clap(long)]
+    pub json: bool,
+    #[command(flatten)]
+    pub generator: Generator,
+}
+
+/// serve 子命令的参数
+#[cfg(feature = "serve")]
+#[derive(Args, Debug)]
+pub struct Serve {
+    /// 监听的地址
+    #[clap(long, value_name = "ADDR", default_value = "127.0.0.1:8080")]
+    pub addr: String,
+    #[command(flatten)]
+    pub generator: Generator,
+}
+
+/// completions 子命令的参数
+#[derive(Args, Debug)]
+pub struct Completions {
+    /// bash、zsh、fish、powershell、elvish
+    #[clap(value_enum)]
+    pub shell: Shell,
+}
+
+impl Completions {
+    /// 输出补全脚本，驱动名等可选值从参数定义中生成，--config 等文件参数补全文件路径
+    pub fn run(&self) {
+        clap_complete::generate(
+            self.shell,
+            &mut Cli::command(),
+            env!("CARGO_PKG_NAME"),
+            &mut io::stdout(),
+        );
+    }
+}
+
+/// 命令行中的子命令名（含别名），没有子命令时返回 None
+pub fn subcommand(args: &[String]) -> Option<&str> {
+    let name = args.get(1)?;
+    Cli::command()
+        .get_subcommands()
+        .any(|c| c.get_name() == name || c.get_all_aliases().any(|a| a == name))
+        .then_some(name.as_str())
+}
@@ -0,0 +1,720 @@
+//! 将 MySQL/PostgreSQL 导出的 DDL 转换为 Sqlite 可以执行的 DDL
+//!
+//! 只处理生成代码需要的部分：建表语句、主键、唯一约束、索引、枚举类型和注释，
+//! 外键、检查约束、触发器、函数等其它语句会被忽略。
+//! Sqlite 不支持的列类型统一声明为 TEXT，原始的类型、默认值和注释单独记录，
+//! 读取表结构后再按目标驱动的规则转换为 Rust 类型。
+
+use std::collections::HashMap;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::Driver;
+
+/// 可能带模式前缀和引号的名称，引号内可以有空白，如："public"."user info"
+const NAME: &str = r#"(?:"[^"]*"|`[^`]*`|[^\s(."`]+)(?:\.(?:"[^"]*"|`[^`]*`|[^\s(."`]+))*"#;
+
+lazy_static! {
+    static ref CREATE_TABLE: Regex = Regex::new(&format!(
+        r"(?is)^CREATE\s+(?:(?:GLOBAL\s+|LOCAL\s+)?(?:TEMPORARY|TEMP|UNLOGGED)\s+)?TABLE\s+(?:IF\s+NOT\s+EXISTS\s+)?({NAME})\s*\("
+    ))
+    .unwrap();
+    static ref CREATE_PARTITION: Regex = Regex::new(
+        r"(?is)^CREATE\s+TABLE\s+(?:IF\s+NOT\s+EXISTS\s+)?(\S+)\s+PARTITION\s+OF\s+([^\s(]+)"
+    )
+    .unwrap();
+    static ref ATTACH_PARTITION: Regex = Regex::new(
+        r"(?is)^ALTER\s+TABLE\s+(?:ONLY\s+)?(\S+)\s+ATTACH\s+PARTITION\s+(\S+)"
+    )
+    .unwrap();
+    static ref SET_DEFAULT: Regex = Regex::new(
+        r"(?is)^ALTER\s+TABLE\s+(?:ONLY\s+)?(\S+)\s+ALTER\s+(?:COLUMN\s+)?(\S+)\s+SET\s+DEFAULT\s+(.*)$"
+    )
+    .unwrap();
+    static ref INHERITS: Regex = Regex::new(r"(?is)\bINHERITS\s*\(([^)]*)\)").unwrap();
+    static ref PARTITION_BY: Regex =
+        Regex::new(r"(?is)\bPARTITION\s+BY\s+(\w+\s*\([^)]*\))").unwrap();
+    static ref CREATE_INDEX: Regex = Regex::new(&format!(
+        r"(?is)^CREATE\s+(UNIQUE\s+)?INDEX\s+(?:CONCURRENTLY\s+)?(?:IF\s+NOT\s+EXISTS\s+)?({NAME})\s+ON\s+(?:ONLY\s+)?({NAME})(?:\s+USING\s+\w+)?\s*\("
+    ))
+    .unwrap();
+    static ref CREATE_ENUM: Regex =
+        Regex::new(r"(?is)^CREATE\s+TYPE\s+(\S+)\s+AS\s+ENUM\s*\((.*)\)$").unwrap();
+    static ref ALTER_CONSTRAINT: Regex = Regex::new(
+        r"(?is)^ALTER\s+TABLE\s+(?:ONLY\s+)?(?:IF\s+EXISTS\s+)?(\S+)\s+ADD\s+(?:CONSTRAINT\s+\S+\s+)?(PRIMARY\s+KEY|UNIQUE)\s*\("
+    )
+    .unwrap();
+    static ref COMMENT_ON: Regex =
+        Regex::new(r"(?is)^COMMENT\s+ON\s+(TABLE|COLUMN|SCHEMA|DATABASE)\s+(\S+)\s+IS\s+'((?:[^']|'')*)'$").unwrap();
+    static ref DATABASE_COMMENT: Regex = Regex::new(
+        r"(?is)^CREATE\s+(?:DATABASE|SCHEMA)\b.*?\bCOMMENT\s*=?\s*'((?:[^']|'')*)'"
+    )
+    .unwrap();
+    static ref TABLE_COMMENT: Regex =
+        Regex::new(r"(?is)\bCOMMENT\s*=?\s*'((?:[^']|'')*)'").unwrap();
+    static ref TABLE_CHARSET: Regex =
+        Regex::new(r"(?i)\b(?:CHARSET|CHARACTER\s+SET)\s*=?\s*(\w+)").unwrap();
+    static ref TABLE_COLLATE: Regex = Regex::new(r"(?i)\bCOLLATE\s*=?\s*(\w+)").unwrap();
+    static ref QUOTED: Regex = Regex::new(r"'((?:[^']|'')*)'").unwrap();
+    /// Sqlite 可以识别的类型名称，如：VARCHAR(50)、DECIMAL(10, 2)
+    static ref SQLITE_TYPE: Regex =
+        Regex::new(r"(?i)^[a-z_][a-z0-9_ ]*(\(\s*\d+\s*(,\s*\d+\s*)?\))?$").unwrap();
+}
+
+/// 列定义中类型之后的关键字
+const COLUMN_KEYWORDS: [&str; 16] = [
+    "NOT",
+    "NULL",
+    "DEFAULT",
+    "PRIMARY",
+    "UNIQUE",
+    "AUTO_INCREMENT",
+    "COMMENT",
+    "REFERENCES",
+    "CHECK",
+    "GENERATED",
+    "AS",
+    "COLLATE",
+    "ON",
+    "CONSTRAINT",
+    "STORAGE",
+    "INVISIBLE",
+];
+
+/// 转换结果
+#[derive(Debug, Default)]
+pub struct Ddl {
+    /// 转换后的 Sqlite 语句
+    pub statements: Vec<String>,
+    /// 原始的列定义，K：(表名, 列名)
+    pub columns: HashMap<(String, String), ColumnDef>,
+    /// 表注释，K：表名
+    pub table_comments: HashMap<String, String>,
+    /// 库注释（PostgreSQL 的 COMMENT ON DATABASE、MariaDB 的 CREATE DATABASE ... COMMENT）
+    pub database_comment: Option<String>,
+    /// public 模式的注释（PostgreSQL）
+    pub schema_comment: Option<String>,
+    /// 自定义的枚举类型（PostgreSQL），K：类型名，V：取值
+    pub enum_types: HashMap<String, Vec<String>>,
+    /// 继承的父表（PostgreSQL），K：表名
+    pub inherits: HashMap<String, Vec<String>>,
+    /// 子分区所属的父表（PostgreSQL），K：子分区表名
+    pub partition_of: HashMap<String, String>,
+    /// 分区键定义（PostgreSQL），K：分区父表名
+    pub partition_keys: HashMap<String, String>,
+}
+
+/// 原始的列定义
+#[derive(Debug, Default, Clone)]
+pub struct ColumnDef {
+    pub column_type: String,
+    pub default: Option<String>,
+    /// 如：on update CURRENT_TIMESTAMP（MySQL）
+    pub extra: Option<String>,
+    /// 生成列的表达式，如：GENERATED ALWAYS AS (price * qty) 中的 price * qty
+    pub generation_expression: Option<String>,
+    pub comment: Option<String>,
+    pub charset: Option<String>,
+    pub collation: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct TableDef {
+    name: String,
+    columns: Vec<String>,
+    constraints: Vec<String>,
+}
+
+/// 转换 DDL，driver 为导出 DDL 的数据库
+pub fn translate(dump: &str, driver: Driver) -> Ddl {
+    let mut ddl = Ddl::default();
+    let mut tables: Vec<TableDef> = vec![];
+    let mut indexes = vec![];
+
+    for statement in split_top_level(&strip_comments(dump, driver), ';') {
+        if let Some(caps) = CREATE_TABLE.captures(&statement) {
+            let name = ident(&caps[1]);
+            let start = caps.get(0).unwrap().end();
+            let Some(end) = closing_paren(&statement, start) else {
+                continue;
+            };
+            let mut table = TableDef {
+                name: name.clone(),
+                ..Default::default()
+            };
+            for item in split_top_level(&statement[start..end], ',') {
+                table_item(&mut ddl, &mut table, &mut indexes, &item);
+            }
+            let options = &statement[end..];
+            if let Some(caps) = INHERITS.captures(options) {
+                let parents = caps[1].split(',').map(ident).collect::<Vec<_>>();
+                for parent in parents.iter() {
+                    inherit_columns(&mut ddl, &tables, parent, &mut table);
+                }
+                ddl.inherits.insert(name.clone(), parents);
+            }
+            if let Some(caps) = PARTITION_BY.captures(options) {
+                ddl.partition_keys.insert(name.clone(), caps[1].to_string());
+            }
+            // MySQL 字符类型的列未指定时使用表的字符集和排序规则
+            let charset = TABLE_CHARSET.captures(options).map(|c| c[1].to_string());
+            let collation = TABLE_COLLATE.captures(options).map(|c| c[1].to_string());
+            for (key, def) in ddl.columns.iter_mut() {
+                let column_type = def.column_type.to_lowercase();
+                let is_text = ["char", "text", "enum(", "set("]
+                    .iter()
+                    .any(|t| column_type.contains(t));
+                if key.0 == name && is_text {
+                    def.charset = def.charset.take().or(charset.clone());
+                    def.collation = def.collation.take().or(collation.clone());
+                }
+            }
+            if let Some(comment) = TABLE_COMMENT.captures(options) {
+                ddl.table_comments
+                    .insert(name, comment[1].replace("''", "'"));
+            }
+            tables.push(table);
+        } else if let Some(caps) = CREATE_PARTITION.captures(&statement) {
+            let (name, parent) = (ident(&caps[1]), ident(&caps[2]));
+            let mut table = TableDef {
+                name: name.clone(),
+                ..Default::default()
+            };
+            inherit_columns(&mut ddl, &tables, &parent, &mut table);
+            ddl.partition_of.insert(name, parent);
+            tables.push(table);
+        } else if let Some(caps) = ATTACH_PARTITION.captures(&statement) {
+            ddl.partition_of.insert(ident(&caps[2]), ident(&caps[1]));
+        } else if let Some(caps) = CREATE_INDEX.captures(&statement) {
+            let start = caps.get(0).unwrap().end() - 1;
+            if let Some(columns) = index_columns(&statement, start) {
+                indexes.push(create_index(
+                    caps.get(1).is_some(),
+                    &ident(&caps[2]),
+                    &ident(&caps[3]),
+                    &columns,
+                ));
+            }
+        } else if let Some(caps) = CREATE_ENUM.captures(&statement) {
+            let values = QUOTED
+                .captures_iter(&caps[2])
+                .map(|v| v[1].replace("''", "'"))
+                .collect();
+            ddl.enum_types.insert(ident(&caps[1]), values);
+        } else if let Some(caps) = ALTER_CONSTRAINT.captures(&statement) {
+            let start = caps.get(0).unwrap().end() - 1;
+            let name = ident(&caps[1]);
+            let Some(columns) = index_columns(&statement, start) else {
+                continue;
+            };
+            let kind = caps[2].split_whitespace().collect::<Vec<_>>().join(" ");
+            if let Some(table) = tables.iter_mut().find(|t| t.name == name) {
+                table
+                    .constraints
+                    .push(format!("{} ({})", kind.to_uppercase(), columns));
+            }
+        } else if let Some(caps) = SET_DEFAULT.captures(&statement) {
+            let key = (ident(&caps[1]), ident(&caps[2]));
+            ddl.columns.entry(key).or_default().default = Some(caps[3].trim().to_string());
+        } else if let Some(caps) = COMMENT_ON.captures(&statement) {
+            let comment = caps[3].replace("''", "'");
+            let parts = split_ident(&caps[2]);
+            if caps[1].eq_ignore_ascii_case("table") {
+                ddl.table_comments
+                    .insert(parts.last().cloned().unwrap_or_default(), comment);
+            } else if caps[1].eq_ignore_ascii_case("database") {
+                ddl.database_comment = Some(comment);
+            } else if caps[1].eq_ignore_ascii_case("schema") {
+                if parts.last().is_some_and(|p| p == "public") {
+                    ddl.schema_comment = Some(comment);
+                }
+            } else if parts.len() >= 2 {
+                let key = (
+                    parts[parts.len() - 2].clone(),
+                    parts[parts.len() - 1].clone(),
+                );
+                ddl.columns.entry(key).or_default().comment = Some(comment);
+            }
+        } else if let Some(caps) = DATABASE_COMMENT.captures(&statement) {
+            ddl.database_comment = Some(caps[1].replace("''", "'"));
+        }
+    }
+
+    for table in tables {
+        let mut items = table.columns;
+        items.extend(table.constraints);
+        ddl.statements.push(format!(
+            "CREATE TABLE \"{}\" (\n    {}\n)",
+            table.name,
+            items.join(",\n    ")
+        ));
+    }
+    ddl.statements.extend(indexes);
+    ddl
+}
+
+/// 建表语句中各列指定的排序规则，K：列名
+pub fn collations(create_table: &str) -> HashMap<String, String> {
+    let mut collations = HashMap::new();
+    let Some(caps) = CREATE_TABLE.captures(create_table) else {
+        return collations;
+    };
+    let start = caps.get(0).unwrap().end();
+    let Some(end) = closing_paren(create_table, start) else {
+        return collations;
+    };
+    for item in split_top_level(&create_table[start..end], ',') {
+        let words = split_words(&item);
+        if let Some(idx) = words.iter().position(|w| w.eq_ignore_ascii_case("COLLATE")) {
+            if let (Some(name), Some(collation)) = (words.first(), words.get(idx + 1)) {
+                collations.insert(ident(name), ident(collation));
+            }
+        }
+    }
+    collations
+}
+
+/// 继承父表的列，子表中同名的列以子表为准
+fn inherit_columns(ddl: &mut Ddl, tables: &[TableDef], parent: &str, table: &mut TableDef) {
+    let Some(parent) = tables.iter().find(|t| t.name == parent) else {
+        return;
+    };
+    let inherited = parent
+        .columns
+        .iter()
+        .filter(|c| {
+            !table
+                .columns
+                .iter()
+                .any(|own| column_name(own) == column_name(c))
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    for column in inherited.iter() {
+        let key = (parent.name.clone(), column_name(column));
+        if let Some(def) = ddl.columns.get(&key).cloned() {
+            ddl.columns
+                .entry((table.name.clone(), column_name(column)))
+                .or_insert(def);
+        }
+    }
+    table.columns.splice(0..0, inherited);
+}
+
+/// 转换后的列定义中的列名
+fn column_name(column: &str) -> String {
+    ident(
+        split_words(column)
+            .first()
+            .map(|w| w.as_str())
+            .unwrap_or_default(),
+    )
+}
+
+/// 建表语句中的一项：列定义或表约束
+fn table_item(ddl: &mut Ddl, table: &mut TableDef, indexes: &mut Vec<String>, item: &str) {
+    let words = split_words(item);
+    let Some(first) = words.first() else {
+        return;
+    };
+    let upper = |i: usize| words.get(i).map(|w| w.to_uppercase()).unwrap_or_default();
+    // 关键字后可能直接跟着括号，如：UNIQUE(email)、DEFAULT(now())
+    let head = |i: usize| upper(i).split('(').next().unwrap_or_default().to_string();
+
+    match head(0).as_str() {
+        "CONSTRAINT" => {
+            // CONSTRAINT 名称 PRIMARY KEY (...)
+            let rest = words.get(2..).unwrap_or_default().join(" ");
+            table_item(ddl, table, indexes, &rest);
+        }
+        "PRIMARY" => {
+            if let Some(columns) = index_columns(item, 0) {
+                table.constraints.push(format!("PRIMARY KEY ({columns})"));
+            }
+        }
+        "UNIQUE" => {
+            if let Some(columns) = index_columns(item, 0) {
+                table.constraints.push(format!("UNIQUE ({columns})"));
+            }
+        }
+        "KEY" | "INDEX" => {
+            if let Some(columns) = index_columns(item, 0) {
+                let name = match words.get(1).and_then(|w| w.split('(').next()) {
+                    Some(name) if !name.is_empty() && head(0) == upper(0) => ident(name),
+                    _ => format!("ix_{}", indexes.len()),
+                };
+                indexes.push(create_index(false, &name, &table.name, &columns));
+            }
+        }
+        "FOREIGN" | "CHECK" | "FULLTEXT" | "SPATIAL" | "EXCLUDE" | "PERIOD" | "LIKE" => {}
+        _ => {
+            let name = ident(first);
+            let mut idx = 1;
+            let mut column_type = vec![];
+            while idx < words.len() {
+                let word = head(idx);
+                let character_set = word == "CHARACTER" && upper(idx + 1) == "SET";
+                if COLUMN_KEYWORDS.contains(&word.as_str()) || character_set {
+                    break;
+                }
+                column_type.push(words[idx].clone());
+                idx += 1;
+            }
+            let column_type = column_type.join(" ");
+
+            let mut def = ddl
+                .columns
+                .remove(&(table.name.clone(), name.clone()))
+                .unwrap_or_default();
+            def.column_type = column_type.clone();
+
+            let mut sqlite = format!(
+                "\"{}\" {}",
+                name,
+                if SQLITE_TYPE.is_match(&column_type) {
+                    column_type.as_str()
+                } else {
+                    "TEXT"
+                }
+            );
+            while idx < words.len() {
+                match head(idx).as_str() {
+                    "NOT" if upper(idx + 1) == "NULL" => sqlite.push_str(" NOT NULL"),
+                    "PRIMARY" if upper(idx + 1) == "KEY" => sqlite.push_str(" PRIMARY KEY"),
+                    "UNIQUE" => sqlite.push_str(" UNIQUE"),
+                    "DEFAULT" if words[idx].len() > "DEFAULT".len() => {
+                        def.default = Some(words[idx]["DEFAULT".len()..].to_string())
+                    }
+                    "DEFAULT" => {
+                        def.default = words
+                            .get(idx + 1)
+                            .filter(|v| !v.eq_ignore_ascii_case("NULL"))
+                            .cloned()
+                    }
+                    "ON" if upper(idx + 1) == "UPDATE" => {
+                        def.extra = words.get(idx + 2).map(|v| format!("on update {v}"))
+                    }
+                    // AS IDENTITY 不是生成列
+                    "AS" if words[idx].len() > "AS".len() => {
+                        let expr = words[idx]["AS".len()..].trim();
+                        def.generation_expression = expr
+                            .strip_prefix('(')
+                            .and_then(|e| e.strip_suffix(')'))
+                            .map(|e| e.split_whitespace().collect::<Vec<_>>().join(" "))
+                    }
+                    "COLLATE" => def.collation = words.get(idx + 1).map(|v| ident(v)),
+                    "CHARACTER" if upper(idx + 1) == "SET" => {
+                        def.charset = words.get(idx + 2).map(|v| ident(v))
+                    }
+                    "COMMENT" => {
+                        def.comment = words
+                            .get(idx + 1)
+                            .and_then(|v| QUOTED.captures(v))
+                            .map(|v| v[1].replace("''", "'"))
+                    }
+                    _ => {}
+                }
+                idx += 1;
+            }
+            table.columns.push(sqlite);
+            ddl.columns.insert((table.name.clone(), name), def);
+        }
+    }
+}
+
+/// 字符类型的长度，如：varchar(50) => 50
+pub fn type_length(column_type: &str) -> Option<i64> {
+    let lower = column_type.to_lowercase();
+    if !lower.contains("char") {
+        return None;
+    }
+    let start = lower.find('(')?;
+    let end = lower[start..].find(')')? + start;
+    lower[start + 1..end].trim().parse().ok()
+}
+
+fn create_index(unique: bool, name: &str, table: &str, columns: &str) -> String {
+    format!(
+        "CREATE {}INDEX \"{table}_{name}\" ON \"{table}\" ({columns})",
+        if unique { "UNIQUE " } else { "" }
+    )
+}
+
+/// 索引或约束中的列，从 start 之后的第一个括号读取，含有表达式时返回 None
+fn index_columns(s: &str, start: usize) -> Option<String> {
+    let open = start + s[start..].find('(')?;
+    let close = closing_paren(s, open + 1)?;
+    let mut columns = vec![];
+    for item in split_top_level(&s[open + 1..close], ',') {
+        // MySQL 的前缀索引 name(10)，排序方向 DESC
+        let word = split_words(&item).into_iter().next()?;
+        let name = word.split('(').next().unwrap_or_default();
+        if name.is_empty() || word.starts_with('(') {
+            return None;
+        }
+        columns.push(format!("\"{}\"", ident(name)));
+    }
+    Some(columns.join(", "))
+}
+
+/// 与 start 之前的左括号配对的右括号位置
+fn closing_paren(s: &str, start: usize) -> Option<usize> {
+    let mut depth = 1;
+    let mut quote = None;
+    for (idx, c) in s[start..].char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"' | '`') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(start + idx);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// 去掉注释：-- 、/* */ 和 MySQL 的 #，PostgreSQL 中 # 是运算符（如 #>>）
+fn strip_comments(s: &str, driver: Driver) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    let mut quote = None;
+    while let Some(c) = chars.next() {
+        if let Some(q) = quote {
+            out.push(c);
+            if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '\'' | '"' | '`' => {
+                quote = Some(c);
+                out.push(c);
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                chars.by_ref().find(|&c| c == '\n');
+                out.push('\n');
+            }
+            '#' if driver == Driver::Mysql => {
+                chars.by_ref().find(|&c| c == '\n');
+                out.push('\n');
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = ' ';
+                for c in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+                out.push(' ');
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// 按顶层的分隔符拆分，忽略引号、括号和 $$ 内的分隔符
+fn split_top_level(s: &str, sep: char) -> Vec<String> {
+    let mut parts = vec![];
+    let mut current = String::new();
+    let mut depth = 0;
+    let mut quote = None;
+    let mut dollar = false;
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '$') if chars.peek() == Some(&'$') => {
+                current.push(c);
+                current.push(chars.next().unwrap());
+                dollar = !dollar;
+                continue;
+            }
+            _ if dollar => {}
+            (None, '\'' | '"' | '`') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') => depth -= 1,
+            (None, c) if c == sep && depth == 0 => {
+                parts.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    parts.push(current);
+    parts
+        .into_iter()
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect()
+}
+
+/// 按顶层的空白拆分为单词，括号和引号内的内容属于同一个单词
+fn split_words(s: &str) -> Vec<String> {
+    let mut words = vec![];
+    let mut current = String::new();
+    let mut depth = 0;
+    let mut quote = None;
+    for c in s.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"' | '`') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') => depth -= 1,
+            (None, c) if c.is_whitespace() && depth == 0 => {
+                if !current.is_empty() {
+                    words.push(std::mem::take(&mut current));
+                }
+                continue;
+            }
+            _ => {}
+        }
+        // 类型与长度之间可能没有空白，也可能有，如：varchar (50)
+        if c == '(' && depth == 1 && current.is_empty() {
+            if let Some(last) = words.pop() {
+                current = last;
+            }
+        }
+        current.push(c);
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+/// 拆分带模式前缀的名称，去掉引号，如："public"."user" => [public, user]
+fn split_ident(s: &str) -> Vec<String> {
+    let mut parts = vec![];
+    let mut current = String::new();
+    let mut quote = None;
+    for c in s.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => current.push(c),
+            (None, '"' | '`') => quote = Some(c),
+            (None, '.') => parts.push(std::mem::take(&mut current)),
+            (None, c) => current.push(c),
+        }
+    }
+    parts.push(current);
+    parts
+}
+
+/// 去掉模式前缀和引号后的名称
+fn ident(s: &str) -> String {
+    split_ident(s).pop().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column<'a>(ddl: &'a Ddl, table: &str, column: &str) -> &'a ColumnDef {
+        &ddl.columns[&(table.to_string(), column.to_string())]
+    }
+
+    #[test]
+    fn strips_comments() {
+        let mysql = "# 用户\nCREATE TABLE user ( -- 主键\n id INT /* 自增 */ NOT NULL # 编号\n)";
+        assert_eq!(
+            strip_comments(mysql, Driver::Mysql),
+            "\nCREATE TABLE user ( \n id INT   NOT NULL \n)"
+        );
+        // PostgreSQL 的 #>> 是运算符，引号内的注释符号保留
+        let postgres = "SELECT data #>> '{a,b}', '-- x' -- 注释\nFROM t";
+        assert_eq!(
+            strip_comments(postgres, Driver::Postgres),
+            "SELECT data #>> '{a,b}', '-- x' \nFROM t"
+        );
+    }
+
+    #[test]
+    fn keeps_postgres_json_operators() {
+        let ddl = translate(
+            "CREATE TABLE doc (\n  id bigint PRIMARY KEY,\n  data jsonb,\n  \
+             title text GENERATED ALWAYS AS (data #>> '{meta,title}') STORED\n);",
+            Driver::Postgres,
+        );
+        assert_eq!(
+            column(&ddl, "doc", "title")
+                .generation_expression
+                .as_deref(),
+            Some("data #>> '{meta,title}'")
+        );
+    }
+
+    #[test]
+    fn unquotes_identifiers() {
+        let ddl = translate(
+            "CREATE TABLE \"public\".\"user info\" (\"order\" integer NOT NULL);\n\
+             CREATE TABLE `shop`.`item` (`key` varchar(20) COMMENT 'it''s');",
+            Driver::Mysql,
+        );
+        assert_eq!(
+            ddl.statements,
+            [
+                "CREATE TABLE \"user info\" (\n    \"order\" integer NOT NULL\n)",
+                "CREATE TABLE \"item\" (\n    \"key\" varchar(20)\n)",
+            ]
+        );
+        assert_eq!(column(&ddl, "item", "key").comment.as_deref(), Some("it's"));
+        assert_eq!(split_ident("\"a.b\".c"), ["a.b", "c"]);
+    }
+
+    #[test]
+    fn reads_generated_columns() {
+        let ddl = translate(
+            "CREATE TABLE line (\n  price DECIMAL(10, 2),\n  qty INT,\n  \
+             total DECIMAL(12, 2) GENERATED ALWAYS AS (price  *  qty) STORED,\n  \
+             id INT GENERATED ALWAYS AS IDENTITY\n)",
+            Driver::Mysql,
+        );
+        let total = column(&ddl, "line", "total");
+        assert_eq!(total.column_type, "DECIMAL(12, 2)");
+        assert_eq!(total.generation_expression.as_deref(), Some("price * qty"));
+        assert_eq!(column(&ddl, "line", "id").generation_expression, None);
+    }
+
+    #[test]
+    fn translates_multiple_statements() {
+        let ddl = translate(
+            "CREATE TYPE mood AS ENUM ('happy', 'sad');\n\
+             CREATE TABLE person (id serial, name varchar(50), mood mood);\n\
+             ALTER TABLE ONLY person ADD CONSTRAINT person_pkey PRIMARY KEY (id);\n\
+             CREATE UNIQUE INDEX person_name ON person USING btree (name);\n\
+             COMMENT ON TABLE person IS '人员';\n\
+             COMMENT ON COLUMN public.person.name IS '姓名;全名';\n\
+             CREATE FUNCTION f() RETURNS int AS $$ SELECT 1; $$ LANGUAGE sql;",
+            Driver::Postgres,
+        );
+        assert_eq!(ddl.enum_types["mood"], ["happy", "sad"]);
+        assert_eq!(
+            ddl.statements,
+            [
+                "CREATE TABLE \"person\" (\n    \"id\" serial,\n    \"name\" varchar(50),\n    \
+                 \"mood\" mood,\n    PRIMARY KEY (\"id\")\n)",
+                "CREATE UNIQUE INDEX \"person_person_name\" ON \"person\" (\"name\")",
+            ]
+        );
+        assert_eq!(ddl.table_comments["person"], "人员");
+        assert_eq!(
+            column(&ddl, "person", "name").comment.as_deref(),
+            Some("姓名;全名")
+        );
+        assert_eq!(column(&ddl, "person", "mood").column_type, "mood");
+    }
+}
@@ -0,0 +1,90 @@
+//! 模板调试
+//!
+//! Tera 渲染出错时只返回 "Failed to render '__tera_one_off'"，
+//! 这里展开完整的错误链。解析错误中带有 Tera 给出的行列；渲染错误不带位置，
+//! 按出错的名称在模板中第一次出现的位置推测，输出中注明是推测的位置。
+
+use std::error::Error;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    /// 渲染错误中出错的变量、过滤器、函数或测试名称
+    static ref ERROR_NAME: Regex =
+        Regex::new(r"^(?:Variable `([^`]+)`|(?:Filter|Function|Test) '([^']+)')").unwrap();
+}
+
+/// 展开 Tera 的错误链，附带出错位置
+pub fn describe_error(template: &str, err: &tera::Error) -> String {
+    let mut messages = vec![];
+    let mut source: Option<&dyn Error> = Some(err);
+    while let Some(e) = source {
+        messages.push(e.to_string());
+        source = e.source();
+    }
+
+    // 解析错误中已经带有 --> 行:列，不再推测
+    let mut description = messages.join("\n");
+    if messages.iter().any(|m| m.contains("-->")) {
+        return description;
+    }
+    if let Some(location) = messages.last().and_then(|m| locate(template, m)) {
+        description.push('\n');
+        description.push_str(&location);
+    }
+    description
+}
+
+/// 根据错误信息中的名称，找到它在模板中第一次出现的位置；
+/// 同一个名称可能出现多次，出错的不一定是第一处
+fn locate(template: &str, message: &str) -> Option<String> {
+    let caps = ERROR_NAME.captures(message)?;
+    let name = caps.get(1).or_else(|| caps.get(2))?.as_str();
+    template.lines().enumerate().find_map(|(idx, line)| {
+        line.find(name).map(|col| {
+            format!(
+                "  --> 模板第 {} 行第 {} 列（按名称第一次出现的位置推测）\n{:>5} | {}",
+                idx + 1,
+                col + 1,
+                idx + 1,
+                line
+            )
+        })
+    })
+}
+
+/// 带行号的文本
+pub fn numbered(contents: &str) -> String {
+    contents
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| format!("{:>5} | {}", idx + 1, line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_parse_error_location() {
+        let template = "{{ name }\n";
+        let err = tera::Tera::one_off(template, &tera::Context::new(), false).unwrap_err();
+        let description = describe_error(template, &err);
+        assert!(description.contains("--> 1:"), "{description}");
+        assert!(!description.contains("推测"), "{description}");
+    }
+
+    #[test]
+    fn marks_guessed_location() {
+        let template = "struct A;\n{{ missing }}\n";
+        let err = tera::Tera::one_off(template, &tera::Context::new(), false).unwrap_err();
+        let description = describe_error(template, &err);
+        assert!(
+            description.contains("模板第 2 行第 4 列（按名称第一次出现的位置推测）"),
+            "{description}"
+        );
+    }
+}
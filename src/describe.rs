@@ -0,0 +1,54 @@
+//! sqlx-db-cli inspect <表名>：输出单张表的列
+//!
+//! 使用与生成时相同的连接参数和类型选项（--portable、--timestamp-policy、--type-map 等），
+//! 输出列名、数据库类型、映射后的 Rust 类型、是否可为空、默认值和列注释，用于排查生成的字段类型。
+
+use crate::Column;
+
+/// 格式化输出，列按表定义中的顺序
+pub fn print(columns: &[&Column]) {
+    let rows = columns
+        .iter()
+        .map(|c| {
+            [
+                crate::sql::raw_name(c).to_string(),
+                c.column_type.clone().unwrap_or_default(),
+                match c.is_nullable {
+                    true => format!("Option<{}>", c.field_type),
+                    false => c.field_type.clone(),
+                },
+                match c.is_nullable {
+                    true => "YES".to_string(),
+                    false => "NO".to_string(),
+                },
+                c.default.clone().unwrap_or_default(),
+                c.comment
+                    .as_deref()
+                    .and_then(|c| c.lines().next())
+                    .unwrap_or_default()
+                    .to_string(),
+            ]
+        })
+        .collect::<Vec<_>>();
+    let header = ["name", "type", "rust type", "null", "default", "comment"].map(String::from);
+
+    // 最后一列（注释）不对齐
+    let mut widths = [0; 5];
+    for row in std::iter::once(&header).chain(rows.iter()) {
+        for (width, cell) in widths.iter_mut().zip(row.iter()) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    for row in std::iter::once(&header).chain(rows.iter()) {
+        let line = row
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| match widths.get(i) {
+                Some(width) => format!("{cell:<width$}"),
+                None => cell.clone(),
+            })
+            .collect::<Vec<_>>()
+            .join("  ");
+        println!("{}", line.trim_end());
+    }
+}
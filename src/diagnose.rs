@@ -0,0 +1,230 @@
+//! 没有读取到表或列时的诊断
+//!
+//! 执行几条轻量的查询（可见的库或模式及其中的表、没有权限的表），
+//! 给出可以着手排查的提示：库名错误、模式不对、账号权限不足或表名拼写错误。
+
+use std::collections::BTreeMap;
+
+use crate::{version::ServerVersion, Driver, Generator, Table};
+
+/// 系统库或模式，不参与诊断
+const MYSQL_SYSTEM_SCHEMAS: &str = "'mysql','information_schema','performance_schema','sys'";
+const POSTGRES_SYSTEM_SCHEMAS: &str = "'pg_catalog','information_schema'";
+
+/// 没有读取到表时的提示
+pub(crate) async fn no_tables(generator: &Generator) -> Vec<String> {
+    let mut hints = vec![];
+    if let Some(file) = generator.from_ddl.as_deref() {
+        hints.push(format!("{file} 中没有可识别的 CREATE TABLE 语句"));
+    }
+    if let Some(file) = generator.offline_catalog.as_deref() {
+        hints.push(format!("{file} 中没有选择的表"));
+    }
+    if generator.only_tagged {
+        hints.push(format!(
+            "指定了 --only-tagged，只生成注释含有 [{}] 的表",
+            generator.comment_tag
+        ));
+    }
+    if generator.from_ddl.is_some() || generator.offline_catalog.is_some() {
+        return hints;
+    }
+
+    let driver = match generator.driver() {
+        Ok(driver) => driver,
+        Err(e) => {
+            hints.push(e.to_string());
+            return hints;
+        }
+    };
+    match visible_tables(generator, driver).await {
+        Ok(visible) => hints.extend(compare(generator, driver, &visible)),
+        Err(e) => hints.push(format!("诊断查询失败：{e}")),
+    }
+    if let Driver::Postgres = driver {
+        match postgres_denied(generator).await {
+            Ok(0) => {}
+            Ok(n) => hints.push(format!(
+                "有 {n} 张表当前账号没有 SELECT 权限，information_schema 中看不到这些表"
+            )),
+            Err(e) => hints.push(format!("诊断查询失败：{e}")),
+        }
+    }
+    hints
+}
+
+/// 读取到表但没有读取到列时的提示
+pub(crate) fn no_columns(generator: &Generator, tables: &[Table]) -> Vec<String> {
+    let names = tables
+        .iter()
+        .map(|t| t.name.as_str())
+        .collect::<Vec<_>>()
+        .join(",");
+    let mut hints = vec![format!(
+        "读取到 {} 张表（{names}）但没有读取到列",
+        tables.len()
+    )];
+    match generator.driver() {
+        Ok(Driver::Mysql | Driver::Postgres) => hints.push(
+            "information_schema.COLUMNS 只返回当前账号有权限的列，检查账号对这些表的列权限"
+                .to_string(),
+        ),
+        Ok(Driver::Sqlite) => hints.push("检查这些表是否为空表或虚拟表".to_string()),
+        Err(_) => {}
+    }
+    hints
+}
+
+/// 根据可见的表给出提示，K：库或模式，V：表名
+fn compare(
+    generator: &Generator,
+    driver: Driver,
+    visible: &BTreeMap<String, Vec<String>>,
+) -> Vec<String> {
+    let mut hints = vec![];
+    let schema = match driver {
+        Driver::Mysql => generator.database_name(),
+        Driver::Postgres => "public".to_string(),
+        Driver::Sqlite => "main".to_string(),
+    };
+    if visible.values().all(|t| t.is_empty()) {
+        hints.push(match driver {
+            Driver::Sqlite => format!(
+                "{} 中没有表，检查文件路径是否正确",
+                generator.database_name()
+            ),
+            _ => "当前账号看不到任何表，检查数据库名称和账号权限（information_schema 只返回有权限的表）"
+                .to_string(),
+        });
+        return hints;
+    }
+
+    let tables = visible.get(&schema).cloned().unwrap_or_default();
+    if tables.is_empty() {
+        let others = visible
+            .iter()
+            .filter(|(_, t)| !t.is_empty())
+            .map(|(s, t)| format!("{s}（{} 张表）", t.len()))
+            .collect::<Vec<_>>()
+            .join("、");
+        hints.push(match driver {
+            Driver::Postgres => {
+                format!("只读取 public 模式，public 中没有表，其他可见的模式：{others}")
+            }
+            _ => format!("{schema} 中没有表，检查数据库名称，其他可见的库：{others}"),
+        });
+        return hints;
+    }
+
+    for name in generator.table_names.split(',').filter(|t| !t.is_empty()) {
+        if tables.iter().any(|t| t == name) {
+            continue;
+        }
+        let similar = tables
+            .iter()
+            .filter(|t| t.eq_ignore_ascii_case(name) || distance(t, name) <= 2)
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        hints.push(match similar.is_empty() {
+            true => format!("{schema} 中没有表 {name}"),
+            false => format!("{schema} 中没有表 {name}，是否为：{}", similar.join("、")),
+        });
+    }
+    if let Some(regex) = &generator.include_regex {
+        if !tables.iter().any(|t| regex.is_match(t)) {
+            hints.push(format!("{schema} 中没有表匹配 --include-regex {regex}"));
+        }
+    }
+    if hints.is_empty() {
+        hints.push(format!(
+            "{schema} 中有 {} 张表，检查 -t 指定的表名和表注释中的 [skip-{}] 标签",
+            tables.len(),
+            generator.comment_tag
+        ));
+    }
+    hints
+}
+
+/// 当前账号可见的表，K：库或模式，V：表名
+async fn visible_tables(
+    generator: &Generator,
+    driver: Driver,
+) -> anyhow::Result<BTreeMap<String, Vec<String>>> {
+    let url = generator.driver_url()?;
+    let rows = match driver {
+        Driver::Mysql => {
+            let version = generator
+                .retry(|| ServerVersion::detect(Driver::Mysql, &url))
+                .await?;
+            let pool = generator.connect::<sqlx::MySql>(&version).await?;
+            sqlx::query_as::<_, (String, Option<String>)>(&format!(
+                "SELECT s.SCHEMA_NAME, t.TABLE_NAME FROM information_schema.SCHEMATA s LEFT JOIN information_schema.`TABLES` t ON t.TABLE_SCHEMA = s.SCHEMA_NAME WHERE s.SCHEMA_NAME NOT IN ({MYSQL_SYSTEM_SCHEMAS})"
+            ))
+            .fetch_all(&pool)
+            .await?
+        }
+        Driver::Postgres => {
+            let version = generator
+                .retry(|| ServerVersion::detect(Driver::Postgres, &url))
+                .await?;
+            let pool = generator.connect::<sqlx::Postgres>(&version).await?;
+            sqlx::query_as::<_, (String, Option<String>)>(&format!(
+                "SELECT n.nspname::TEXT, t.table_name::TEXT FROM pg_namespace n LEFT JOIN information_schema.tables t ON t.table_schema = n.nspname WHERE n.nspname NOT IN ({POSTGRES_SYSTEM_SCHEMAS}) AND n.nspname NOT LIKE 'pg_%'"
+            ))
+            .fetch_all(&pool)
+            .await?
+        }
+        Driver::Sqlite => {
+            let pool = generator
+                .connect::<sqlx::Sqlite>(&ServerVersion::default())
+                .await?;
+            let mut rows = vec![];
+            for schema in generator.sqlite_schemas() {
+                rows.extend(
+                    sqlx::query_as::<_, (String, Option<String>)>(&format!(
+                        "SELECT '{schema}', name FROM \"{schema}\".sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'"
+                    ))
+                    .fetch_all(&pool)
+                    .await?,
+                );
+            }
+            rows
+        }
+    };
+
+    let mut visible: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (schema, table) in rows {
+        let tables = visible.entry(schema).or_default();
+        tables.extend(table);
+    }
+    Ok(visible)
+}
+
+/// 当前账号没有 SELECT 权限的表的数量
+async fn postgres_denied(generator: &Generator) -> anyhow::Result<i64> {
+    let url = generator.driver_url()?;
+    let version = generator
+        .retry(|| ServerVersion::detect(Driver::Postgres, &url))
+        .await?;
+    let pool = generator.connect::<sqlx::Postgres>(&version).await?;
+    Ok(sqlx::query_scalar::<_, i64>(&format!(
+        "SELECT count(*) FROM pg_class c JOIN pg_namespace n ON n.OID = c.relnamespace WHERE c.relkind IN ('r', 'p') AND n.nspname NOT IN ({POSTGRES_SYSTEM_SCHEMAS}) AND n.nspname NOT LIKE 'pg_%' AND NOT has_table_privilege(c.OID, 'SELECT')"
+    ))
+    .fetch_one(&pool)
+    .await?)
+}
+
+/// 编辑距离，用于提示拼写错误的表名
+fn distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut prev = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            cur.push((prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1));
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
@@ -0,0 +1,47 @@
+//! 命名字典
+//!
+//! 老系统的表名、列名往往不适合直接转换（如 `gmt_create`），字典中指定的名称优先于默认的命名转换。
+//!
+//! ```toml
+//! [tables]
+//! stock_info = "Inventory"
+//!
+//! [columns]
+//! gmt_create = "created_at"
+//! # 只对指定表生效，优先于不带表名的配置
+//! "order_info.gmt_create" = "placed_at"
+//! ```
+
+use std::{collections::HashMap, fs};
+
+use serde::Deserialize;
+
+#[derive(Deserialize, Default, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct Dictionary {
+    /// K：表名，V：结构体名称
+    pub tables: HashMap<String, String>,
+    /// K：列名或 表名.列名，V：字段名称
+    pub columns: HashMap<String, String>,
+}
+
+impl Dictionary {
+    /// 读取字典文件，作为 clap 的 value_parser 使用
+    pub fn load(path: &str) -> Result<Self, String> {
+        let contents = fs::read_to_string(path).map_err(|e| format!("读取 {path} 失败：{e}"))?;
+        toml::from_str(&contents).map_err(|e| format!("解析 {path} 失败：{e}"))
+    }
+
+    /// 表对应的结构体名称
+    pub fn struct_name(&self, table: &str) -> Option<&str> {
+        self.tables.get(table).map(String::as_str)
+    }
+
+    /// 列对应的字段名称
+    pub fn field_name(&self, table: &str, column: &str) -> Option<&str> {
+        self.columns
+            .get(&format!("{table}.{column}"))
+            .or_else(|| self.columns.get(column))
+            .map(String::as_str)
+    }
+}
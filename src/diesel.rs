@@ -0,0 +1,300 @@
+//! Diesel 的表结构定义
+//!
+//! `--emit diesel-schema` 时根据同一份表结构生成 Diesel 的 schema.rs（`diesel::table!`），
+//! 从 sqlx 迁移到 Diesel（或反过来）期间两边的定义来自同一个数据源。
+//! schema.rs 不在 mod.rs 中声明，生成的模型不依赖 Diesel，使用 Diesel 的服务复制或用 `#[path]` 引用即可。
+//!
+//! 类型按数据库中的列类型映射为 `diesel::sql_types` 中的类型，PostgreSQL 的枚举、自定义类型和 MySQL 的枚举
+//! 生成到 `sql_types` 模块中；没有主键的表 Diesel 无法使用，跳过并提示。
+
+use std::collections::{BTreeMap, HashMap};
+
+use heck::ToUpperCamelCase;
+use serde::Serialize;
+
+use crate::{sql, Column, Driver, Generator, Table, KEYWORDS};
+
+/// schema.rs 中的自定义类型
+#[derive(Debug, Clone, Serialize)]
+pub struct SqlType {
+    pub name: String,
+    /// 如：postgres_type(name = "mood")
+    pub attr: String,
+}
+
+/// diesel::table! 中的一张表
+#[derive(Debug, Clone, Serialize)]
+pub struct DieselTable {
+    /// 文档注释，每行一项
+    pub doc: Vec<String>,
+    /// 表名与模块名不同时的原表名
+    pub sql_name: Option<String>,
+    /// 模块名，其他库或模式中的表带上库名或模式名，如：billing.invoice
+    pub name: String,
+    /// 只有模块名，用于 allow_tables_to_appear_in_same_query!
+    pub ident: String,
+    pub primary_key: Vec<String>,
+    pub columns: Vec<DieselColumn>,
+    /// 用到的自定义类型
+    pub sql_types: Vec<String>,
+    pub feature: Option<String>,
+}
+
+/// diesel::table! 中的一列
+#[derive(Debug, Clone, Serialize)]
+pub struct DieselColumn {
+    pub doc: Vec<String>,
+    /// 列名与字段名不同时的原列名
+    pub sql_name: Option<String>,
+    pub name: String,
+    pub sql_type: String,
+}
+
+/// 生成 schema.rs 需要的全部信息
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DieselSchema {
+    pub sql_types: Vec<SqlType>,
+    pub tables: Vec<DieselTable>,
+    /// allow_tables_to_appear_in_same_query! 中的表，按 feature 控制的表不一定存在，不放在其中
+    pub same_query: Vec<String>,
+}
+
+/// 按 mod.rs 中模块的顺序收集每张表的定义
+pub(crate) fn collect(
+    generator: &Generator,
+    table_map: &HashMap<String, Table>,
+    tables_columns: &[Column],
+) -> anyhow::Result<DieselSchema> {
+    let driver = generator.driver()?;
+    let default_schema = match driver {
+        Driver::Mysql => generator.database_name(),
+        Driver::Postgres => "public".to_string(),
+        Driver::Sqlite => String::new(),
+    };
+    let mut sql_types = BTreeMap::new();
+    let mut tables = vec![];
+    for table_name in generator
+        .module_sections(table_map)
+        .iter()
+        .flat_map(|s| s.tables.iter())
+    {
+        let table = &table_map[table_name];
+        let mut columns = tables_columns
+            .iter()
+            .filter(|c| c.table_name.as_ref() == Some(table_name))
+            .collect::<Vec<_>>();
+        columns.sort_by_key(|c| c.ordinal);
+        if !columns.iter().any(|c| c.is_primary_key) {
+            tracing::warn!("{table_name} has no primary key, it is skipped in diesel schema");
+            continue;
+        }
+
+        let mut used = vec![];
+        let mut primary_key = vec![];
+        let columns = columns
+            .into_iter()
+            .map(|c| {
+                let (sql_type, custom) = sql_type(driver, c);
+                if let Some(custom) = custom {
+                    used.push(custom.name.clone());
+                    sql_types.insert(custom.name.clone(), custom);
+                }
+                let column = DieselColumn::new(c, sql_type);
+                if c.is_primary_key {
+                    primary_key.push(column.name.clone());
+                }
+                column
+            })
+            .collect::<Vec<DieselColumn>>();
+        used.sort();
+        used.dedup();
+
+        let ident = generator.module_name(table_name);
+        let name = match driver != Driver::Sqlite
+            && !table.schema.is_empty()
+            && table.schema != default_schema
+        {
+            true => format!("{}.{ident}", table.schema),
+            false => ident.clone(),
+        };
+        tables.push(DieselTable {
+            doc: doc_lines(&table.comment),
+            sql_name: (&ident != table_name).then(|| table_name.clone()),
+            name,
+            ident,
+            primary_key,
+            columns,
+            sql_types: used,
+            feature: generator.table_feature(table_name).map(str::to_string),
+        });
+    }
+    Ok(DieselSchema {
+        sql_types: sql_types.into_values().collect(),
+        same_query: tables
+            .iter()
+            .filter(|t| t.feature.is_none())
+            .map(|t| t.ident.clone())
+            .collect(),
+        tables,
+    })
+}
+
+impl DieselColumn {
+    fn new(column: &Column, sql_type: String) -> Self {
+        let raw = sql::raw_name(column);
+        let field = column
+            .name
+            .as_deref()
+            .unwrap_or_default()
+            .trim_start_matches("r#");
+        // 关键字按 Diesel CLI 的习惯加上 _
+        let name = match KEYWORDS.contains(&field) {
+            true => format!("{field}_"),
+            false => field.to_string(),
+        };
+        // Sqlite 的 INTEGER PRIMARY KEY 报告为可为空，主键在 Diesel 中不能为空
+        let sql_type = match column.is_nullable && !column.is_primary_key {
+            true => format!("Nullable<{sql_type}>"),
+            false => sql_type,
+        };
+        Self {
+            doc: doc_lines(column.comment.as_deref().unwrap_or_default()),
+            sql_name: (name != raw).then(|| raw.to_string()),
+            name,
+            sql_type,
+        }
+    }
+}
+
+/// 注释按行拆分，去掉空行
+fn doc_lines(comment: &str) -> Vec<String> {
+    comment
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// 列对应的 Diesel 类型，以及需要生成的自定义类型
+fn sql_type(driver: Driver, column: &Column) -> (String, Option<SqlType>) {
+    let column_type = column.column_type.as_deref().unwrap_or_default();
+    match driver {
+        Driver::Postgres => {
+            let (udt, is_array) = match column_type.strip_prefix('_') {
+                Some(inner) => (inner, true),
+                None => (column_type, false),
+            };
+            let (name, custom) = match postgres_type(udt) {
+                Some(name) => (name.to_string(), None),
+                None => {
+                    let name = udt.to_upper_camel_case();
+                    let custom = SqlType {
+                        name: name.clone(),
+                        attr: format!("postgres_type(name = {udt:?})"),
+                    };
+                    (name, Some(custom))
+                }
+            };
+            match is_array {
+                true => (format!("Array<{name}>"), custom),
+                false => (name, custom),
+            }
+        }
+        Driver::Mysql => {
+            let lower = column_type.to_lowercase();
+            let base = lower
+                .split(['(', ' '])
+                .next()
+                .unwrap_or_default()
+                .to_string();
+            if matches!(base.as_str(), "enum" | "set") {
+                let name = format!(
+                    "{}{}{}",
+                    column.table_name.as_deref().unwrap_or_default(),
+                    sql::raw_name(column),
+                    base
+                )
+                .to_upper_camel_case();
+                let custom = SqlType {
+                    name: name.clone(),
+                    attr: format!("mysql_type(name = {:?})", base.to_upper_camel_case()),
+                };
+                return (name, Some(custom));
+            }
+            let name = match base.as_str() {
+                "tinyint" if lower.starts_with("tinyint(1)") => "Bool",
+                "bool" | "boolean" => "Bool",
+                "tinyint" => "TinyInt",
+                "smallint" | "year" => "SmallInt",
+                "mediumint" | "int" | "integer" => "Integer",
+                "bigint" => "BigInt",
+                "float" => "Float",
+                "double" | "real" => "Double",
+                "decimal" | "numeric" => "Numeric",
+                "date" => "Date",
+                "time" => "Time",
+                "datetime" => "Datetime",
+                "timestamp" => "Timestamp",
+                "json" => "Json",
+                "binary" | "varbinary" | "tinyblob" | "blob" | "mediumblob" | "longblob"
+                | "bit" => "Binary",
+                "char" | "varchar" => "Varchar",
+                _ => "Text",
+            };
+            match lower.contains("unsigned") && name != "Bool" {
+                true => (format!("Unsigned<{name}>"), None),
+                false => (name.to_string(), None),
+            }
+        }
+        Driver::Sqlite => {
+            let upper = column_type.to_uppercase();
+            let name = match upper.split('(').next().unwrap_or_default().trim() {
+                "BOOLEAN" | "BOOL" => "Bool",
+                "SMALLINT" => "SmallInt",
+                "BIGINT" => "BigInt",
+                "DATE" => "Date",
+                "TIME" => "Time",
+                "DATETIME" | "TIMESTAMP" => "Timestamp",
+                "FLOAT" => "Float",
+                // 其他类型按 SQLite 的类型亲和性
+                t if t.contains("INT") => "Integer",
+                t if t.contains("CHAR") || t.contains("CLOB") || t.contains("TEXT") => "Text",
+                t if t.is_empty() || t.contains("BLOB") => "Binary",
+                _ => "Double",
+            };
+            (name.to_string(), None)
+        }
+    }
+}
+
+/// PostgreSQL 的类型名（udt_name）对应的 Diesel 类型，自定义类型为 None
+fn postgres_type(udt: &str) -> Option<&'static str> {
+    Some(match udt {
+        "bool" => "Bool",
+        "int2" => "Int2",
+        "int4" => "Int4",
+        "int8" => "Int8",
+        "float4" => "Float4",
+        "float8" => "Float8",
+        "numeric" => "Numeric",
+        "money" => "Money",
+        "text" | "citext" | "name" => "Text",
+        "varchar" => "Varchar",
+        "bpchar" => "Bpchar",
+        "bytea" => "Bytea",
+        "date" => "Date",
+        "time" | "timetz" => "Time",
+        "timestamp" => "Timestamp",
+        "timestamptz" => "Timestamptz",
+        "interval" => "Interval",
+        "uuid" => "Uuid",
+        "json" => "Json",
+        "jsonb" => "Jsonb",
+        "inet" => "Inet",
+        "cidr" => "Cidr",
+        "macaddr" => "MacAddr",
+        "oid" => "Oid",
+        _ => return None,
+    })
+}
@@ -0,0 +1,100 @@
+//! `check`（`--dry-run`）时有变化的文件的差异摘要
+//!
+//! CI 中检查失败时只知道哪些文件有变化并不够，还需要知道变化的内容，才能判断是表结构改了没有重新生成，
+//! 还是升级了 sqlx-db-cli。这里按行比较，只输出变化的行及其行号，每个文件最多输出 [`MAX_LINES`] 行。
+
+/// 每个文件最多输出的变化行数
+pub const MAX_LINES: usize = 40;
+
+/// 最长公共子序列的表格大小上限，超过时中间部分整体按删除和新增处理
+const MAX_CELLS: usize = 4_000_000;
+
+/// 变化的一行
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Line<'a> {
+    /// 旧文件中的行号（从 1 开始）和内容
+    Removed(usize, &'a str),
+    /// 新文件中的行号（从 1 开始）和内容
+    Added(usize, &'a str),
+}
+
+/// 按行比较，返回删除和新增的行，顺序与文件中一致
+pub fn lines<'a>(old: &'a str, new: &'a str) -> Vec<Line<'a>> {
+    let old = old.lines().collect::<Vec<_>>();
+    let new = new.lines().collect::<Vec<_>>();
+    // 去掉相同的开头和结尾，通常只剩下很少的行
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let a = &old[prefix..old.len() - suffix];
+    let b = &new[prefix..new.len() - suffix];
+
+    let mut diff = vec![];
+    if a.len() * b.len() > MAX_CELLS {
+        diff.extend(
+            a.iter()
+                .enumerate()
+                .map(|(i, l)| Line::Removed(prefix + i + 1, l)),
+        );
+        diff.extend(
+            b.iter()
+                .enumerate()
+                .map(|(j, l)| Line::Added(prefix + j + 1, l)),
+        );
+        return diff;
+    }
+    // lcs[i][j] 为 a[i..] 与 b[j..] 的最长公共子序列长度
+    let width = b.len() + 1;
+    let mut lcs = vec![0u32; (a.len() + 1) * width];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i * width + j] = match a[i] == b[j] {
+                true => lcs[(i + 1) * width + j + 1] + 1,
+                false => lcs[(i + 1) * width + j].max(lcs[i * width + j + 1]),
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            i += 1;
+            j += 1;
+        } else if i < a.len()
+            && (j == b.len() || lcs[(i + 1) * width + j] >= lcs[i * width + j + 1])
+        {
+            diff.push(Line::Removed(prefix + i + 1, a[i]));
+            i += 1;
+        } else {
+            diff.push(Line::Added(prefix + j + 1, b[j]));
+            j += 1;
+        }
+    }
+    diff
+}
+
+/// 差异摘要：新增和删除的行数，以及最多 [`MAX_LINES`] 行变化的内容，空行不计入
+pub fn summary(old: &str, new: &str) -> String {
+    let diff = lines(old, new)
+        .into_iter()
+        .filter(|l| match l {
+            Line::Removed(_, l) | Line::Added(_, l) => !l.trim().is_empty(),
+        })
+        .collect::<Vec<_>>();
+    let added = diff.iter().filter(|l| matches!(l, Line::Added(..))).count();
+    let mut summary = format!("(+{added} -{})", diff.len() - added);
+    for line in diff.iter().take(MAX_LINES) {
+        let (sign, number, contents) = match line {
+            Line::Removed(n, l) => ('-', n, l),
+            Line::Added(n, l) => ('+', n, l),
+        };
+        summary.push_str(&format!("\n  {number:>5} {sign} {}", contents.trim_end()));
+    }
+    if diff.len() > MAX_LINES {
+        summary.push_str(&format!("\n  ... {} more lines", diff.len() - MAX_LINES));
+    }
+    summary
+}
@@ -0,0 +1,133 @@
+//! 枚举类型
+//!
+//! MySQL 的 enum 列和 PostgreSQL 的枚举类型会生成对应的 Rust 枚举，
+//! 并附带 Display、FromStr 和 serde 实现，可直接用于路径参数和查询参数。
+
+use heck::ToUpperCamelCase;
+use regex::Regex;
+use serde::Serialize;
+
+use crate::Column;
+
+/// 生成的枚举
+#[derive(Serialize, Debug, Clone)]
+pub struct EnumType {
+    /// Rust 类型名称
+    pub name: String,
+    /// 数据库中的类型名称
+    pub type_name: String,
+    pub variants: Vec<Variant>,
+}
+
+/// 枚举成员
+#[derive(Serialize, Debug, Clone)]
+pub struct Variant {
+    /// Rust 成员名称
+    pub name: String,
+    /// 数据库中的取值
+    pub value: String,
+    /// 取值对应的 Rust 字符串字面量，已包含引号和转义
+    pub literal: String,
+}
+
+impl EnumType {
+    fn new(column: &Column) -> Self {
+        Self {
+            name: column.field_type.clone(),
+            type_name: column.column_type.clone().unwrap_or_default(),
+            variants: column
+                .enum_values
+                .iter()
+                .map(|value| Variant {
+                    name: variant_name(value),
+                    value: value.clone(),
+                    literal: format!("{value:?}"),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// 收集列中用到的枚举，同名枚举只保留一个
+pub fn collect<'a>(columns: impl IntoIterator<Item = &'a Column>) -> Vec<EnumType> {
+    let mut enums: Vec<EnumType> = vec![];
+    for column in columns {
+        if column.enum_values.is_empty() || enums.iter().any(|e| e.name == column.field_type) {
+            continue;
+        }
+        enums.push(EnumType::new(column));
+    }
+    enums.sort_by(|a, b| a.name.cmp(&b.name));
+    enums
+}
+
+/// 解析 MySQL 的枚举定义，如：enum('a','b')
+pub fn parse_mysql_enum(column_type: &str) -> Vec<String> {
+    if !column_type.to_lowercase().starts_with("enum(") {
+        return vec![];
+    }
+    let rg = Regex::new("'((?:[^']|'')*)'").unwrap();
+    rg.captures_iter(column_type)
+        .filter_map(|caps| caps.get(1))
+        .map(|v| v.as_str().replace("''", "'"))
+        .collect()
+}
+
+/// 枚举成员名称，不能以数字开头或为空
+fn variant_name(value: &str) -> String {
+    let name = value.to_upper_camel_case();
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("V{name}")
+    } else {
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(field_type: &str, enum_values: &[&str]) -> Column {
+        Column {
+            field_type: field_type.to_string(),
+            column_type: Some("order_status".to_string()),
+            enum_values: enum_values.iter().map(|v| v.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parses_mysql_enum() {
+        assert_eq!(
+            parse_mysql_enum("enum('new','paid','it''s')"),
+            vec!["new", "paid", "it's"]
+        );
+        assert_eq!(parse_mysql_enum("ENUM('a')"), vec!["a"]);
+        assert!(parse_mysql_enum("varchar(32)").is_empty());
+        assert!(parse_mysql_enum("set('a','b')").is_empty());
+    }
+
+    #[test]
+    fn names_variants() {
+        assert_eq!(variant_name("in_progress"), "InProgress");
+        assert_eq!(variant_name("1st"), "V1st");
+        assert_eq!(variant_name(""), "V");
+    }
+
+    #[test]
+    fn collects_distinct_enums() {
+        let status = column("OrderStatus", &["new", "paid"]);
+        let same = column("OrderStatus", &["new", "paid"]);
+        let kind = column("OrderKind", &["a"]);
+        let plain = column("String", &[]);
+        let enums = collect([&status, &same, &kind, &plain]);
+        assert_eq!(
+            enums.iter().map(|e| e.name.as_str()).collect::<Vec<_>>(),
+            vec!["OrderKind", "OrderStatus"]
+        );
+        let variants = &enums[1].variants;
+        assert_eq!(variants[1].name, "Paid");
+        assert_eq!(variants[1].value, "paid");
+        assert_eq!(variants[1].literal, "\"paid\"");
+    }
+}
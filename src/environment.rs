@@ -0,0 +1,199 @@
+//! 按环境生成并比较
+//!
+//! `--environment prod=prod.json --environment staging=staging.json` 从各环境用 `--save-snapshot` 保存的表结构快照
+//! 分别生成模型到输出目录下的 `prod/`、`staging/` 子模块，迁移测试代码可以同时引用两种表结构；
+//! 输出目录的 `mod.rs` 声明这些模块，`ENVIRONMENTS.md` 以第一个环境为基准列出其他环境中表和列的差异。
+
+use std::{collections::BTreeMap, str::FromStr};
+
+use heck::ToSnakeCase;
+use serde::{Deserialize, Serialize};
+
+use crate::SchemaModel;
+
+/// 差异报告的文件名
+pub const REPORT_FILE: &str = "ENVIRONMENTS.md";
+
+/// 一个环境：模块名和快照文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Environment {
+    pub name: String,
+    pub snapshot: String,
+}
+
+impl FromStr for Environment {
+    type Err = String;
+
+    /// prod=prod.json
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, snapshot) = s
+            .split_once('=')
+            .ok_or_else(|| format!("{s} 格式错误，应为：环境名=快照文件"))?;
+        let name = name.trim();
+        if name.is_empty() || name.to_snake_case() != name {
+            return Err(format!("环境名 {name} 需要是 snake_case 的模块名"));
+        }
+        Ok(Self {
+            name: name.to_string(),
+            snapshot: snapshot.trim().to_string(),
+        })
+    }
+}
+
+/// 输出目录的 mod.rs，每个环境一个模块
+pub fn mod_rs(environments: &[Environment]) -> String {
+    let mut contents = "//! 由 sqlx-db-cli 按环境生成，差异见 ENVIRONMENTS.md\n\n".to_string();
+    for environment in environments {
+        contents.push_str(&format!("pub mod {};\n", environment.name));
+    }
+    contents
+}
+
+/// 以第一个环境为基准，列出其他环境中新增、缺少的表和列以及 Rust 类型不同的列
+///
+/// models 中列的类型需要已经过类型映射
+pub fn report(models: &[(String, SchemaModel)]) -> String {
+    let Some(((base, base_model), others)) = models.split_first() else {
+        return String::new();
+    };
+    let base_tables = field_types(base_model);
+    let mut contents = format!("# 环境差异\n\n以 `{base}` 为基准。\n");
+    for (name, model) in others {
+        contents.push_str(&format!("\n## {name}\n\n"));
+        let tables = field_types(model);
+        let mut names = base_tables.keys().chain(tables.keys()).collect::<Vec<_>>();
+        names.sort();
+        names.dedup();
+
+        let mut same = true;
+        for table in names {
+            let (base_columns, columns) = match (base_tables.get(table), tables.get(table)) {
+                (Some(base_columns), Some(columns)) => (base_columns, columns),
+                (Some(_), None) => {
+                    contents.push_str(&format!("- `{table}`：{name} 中没有\n"));
+                    same = false;
+                    continue;
+                }
+                _ => {
+                    contents.push_str(&format!("- `{table}`：只在 {name} 中\n"));
+                    same = false;
+                    continue;
+                }
+            };
+            if base_columns == columns {
+                continue;
+            }
+            same = false;
+            contents.push_str(&format!("- `{table}`\n"));
+            for (column, field_type) in columns.iter() {
+                if !base_columns.contains_key(column) {
+                    contents.push_str(&format!("  - 新增 `{column}: {field_type}`\n"));
+                }
+            }
+            for column in base_columns.keys() {
+                if !columns.contains_key(column) {
+                    contents.push_str(&format!("  - 缺少 `{column}`\n"));
+                }
+            }
+            for (column, base_type) in base_columns.iter() {
+                match columns.get(column) {
+                    Some(field_type) if field_type != base_type => contents
+                        .push_str(&format!("  - `{column}`：`{base_type}` → `{field_type}`\n")),
+                    _ => {}
+                }
+            }
+        }
+        if same {
+            contents.push_str("表结构相同。\n");
+        }
+    }
+    contents
+}
+
+/// 按表名、列名整理的字段类型，与生成的结构体字段一致，可为空的列为 Option
+fn field_types(model: &SchemaModel) -> BTreeMap<&str, BTreeMap<&str, String>> {
+    let mut tables = model
+        .tables
+        .iter()
+        .map(|t| (t.name.as_str(), BTreeMap::new()))
+        .collect::<BTreeMap<_, _>>();
+    for column in model.columns.iter() {
+        let (Some(table), Some(name)) = (&column.table_name, &column.name) else {
+            continue;
+        };
+        if let Some(columns) = tables.get_mut(table.as_str()) {
+            let field_type = match column.is_nullable {
+                true => format!("Option<{}>", column.field_type),
+                false => column.field_type.clone(),
+            };
+            columns.insert(name.trim_start_matches("r#"), field_type);
+        }
+    }
+    tables
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Column, Table};
+
+    fn model(tables: &[(&str, &[(&str, &str)])]) -> SchemaModel {
+        SchemaModel::new(
+            tables
+                .iter()
+                .map(|(name, _)| Table {
+                    name: name.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+            tables
+                .iter()
+                .flat_map(|(table, columns)| {
+                    columns.iter().map(|(name, field_type)| Column {
+                        table_name: Some(table.to_string()),
+                        name: Some(name.to_string()),
+                        field_type: field_type.to_string(),
+                        ..Default::default()
+                    })
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn parses_environment() {
+        let environment = "prod=dump/prod.json".parse::<Environment>().unwrap();
+        assert_eq!(environment.name, "prod");
+        assert_eq!(environment.snapshot, "dump/prod.json");
+        assert!("prod.json".parse::<Environment>().is_err());
+        assert!("Prod=prod.json".parse::<Environment>().is_err());
+    }
+
+    #[test]
+    fn reports_differences_from_first_environment() {
+        let prod = model(&[
+            ("user", &[("id", "i64"), ("name", "String")]),
+            ("audit", &[("id", "i64")]),
+        ]);
+        let staging = model(&[
+            (
+                "user",
+                &[("id", "i64"), ("name", "Option<String>"), ("age", "i32")],
+            ),
+            ("coupon", &[("id", "i64")]),
+        ]);
+        let report = report(&[
+            ("prod".to_string(), prod.clone()),
+            ("staging".to_string(), staging),
+            ("test".to_string(), prod),
+        ]);
+        assert_eq!(
+            report,
+            "# 环境差异\n\n以 `prod` 为基准。\n\n## staging\n\n\
+             - `audit`：staging 中没有\n\
+             - `coupon`：只在 staging 中\n\
+             - `user`\n  - 新增 `age: i32`\n  - `name`：`String` → `Option<String>`\n\
+             \n## test\n\n表结构相同。\n"
+        );
+    }
+}
@@ -0,0 +1,55 @@
+//! 错误分类及进程退出码
+//!
+//! 各阶段的错误仍然以 `anyhow::Error` 传递，在连接、读取表结构、渲染、写入文件的边界处包装为 [`Error`]，
+//! 入口根据错误链中的 [`Error`] 选择退出码，CI 可以据此区分是数据库不可用还是模板有问题。
+
+/// 包装的原始错误
+pub type Source = Box<dyn std::error::Error + Send + Sync>;
+
+/// 生成过程中的错误
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// 连接数据库失败
+    #[error("连接数据库失败")]
+    Connect(#[source] Source),
+    /// 读取表结构失败
+    #[error("读取表结构失败")]
+    Introspect(#[source] Source),
+    /// 渲染模板失败，附带完整的错误原因和出错位置
+    #[error("渲染模板错误：{0}")]
+    Render(String),
+    /// 部分表渲染失败，其他表已经正常生成
+    #[error("{} 张表渲染失败：{}", .0.len(), .0.join(", "))]
+    RenderTables(Vec<String>),
+    /// 写入文件失败
+    #[error("写入文件失败")]
+    Write(#[source] Source),
+}
+
+impl Error {
+    /// 进程退出码：3 连接失败，4 读取表结构失败，5 渲染失败，6 写入文件失败
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Error::Connect(_) => 3,
+            Error::Introspect(_) => 4,
+            Error::Render(_) | Error::RenderTables(_) => 5,
+            Error::Write(_) => 6,
+        }
+    }
+
+    /// 错误链中的 [`Error`] 对应的退出码，没有分类的错误（参数、配置等）为 2
+    pub fn exit_code_of(e: &anyhow::Error) -> u8 {
+        e.chain()
+            .find_map(|e| e.downcast_ref::<Error>())
+            .map_or(2, Error::exit_code)
+    }
+}
+
+/// 没有分类的错误包装为指定的分类，已经分类的错误保持不变
+pub(crate) fn classify(e: anyhow::Error, class: fn(Source) -> Error) -> anyhow::Error {
+    if e.chain().any(|e| e.is::<Error>()) {
+        e
+    } else {
+        class(e.into()).into()
+    }
+}
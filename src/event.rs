@@ -0,0 +1,94 @@
+//! 事件消息结构体
+//!
+//! `--emit events` 时为每张表生成只依赖 serde 的消息结构体和从表结构体的转换，
+//! 用于 CDC/outbox 将表中的行作为消息发布，默认包含全部列，可以用 `--event-columns` 指定。
+
+use std::{collections::HashMap, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{parse_group, sql, Column, Generator, Table};
+
+/// 事件消息包含的列
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventColumns {
+    pub table: String,
+    pub columns: Vec<String>,
+}
+
+impl FromStr for EventColumns {
+    type Err = String;
+
+    /// order_info=id,status,amount
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (table, columns) = parse_group(s, "表名")?;
+        Ok(Self { table, columns })
+    }
+}
+
+/// 单张表的事件消息
+#[derive(Debug, Clone, Serialize)]
+pub struct EventPayload {
+    /// 表结构体名称
+    pub struct_name: String,
+    /// 表注释，为空时为表名
+    pub comment: String,
+    /// 表所属的 feature
+    pub feature: Option<String>,
+    /// 表的主键是否使用 newtype，转换时取出包装的值
+    pub id_newtype: bool,
+    pub columns: Vec<Column>,
+}
+
+/// 按 mod.rs 中模块的顺序收集每张表的事件消息
+pub(crate) fn collect(
+    generator: &Generator,
+    table_map: &HashMap<String, Table>,
+    tables_columns: &[Column],
+) -> Vec<EventPayload> {
+    generator
+        .module_sections(table_map)
+        .iter()
+        .flat_map(|s| s.tables.iter())
+        .map(|table_name| {
+            let table = &table_map[table_name];
+            let selected = generator
+                .event_columns
+                .iter()
+                .find(|e| &e.table == table_name)
+                .map(|e| e.columns.as_slice());
+            let mut columns = tables_columns
+                .iter()
+                .filter(|c| c.table_name.as_ref() == Some(table_name))
+                .filter(|c| selected.is_none_or(|s| s.iter().any(|n| n == sql::raw_name(c))))
+                .cloned()
+                .collect::<Vec<_>>();
+            columns.sort_by_key(|c| c.ordinal);
+            if let Some(selected) = selected {
+                for name in selected {
+                    if !columns.iter().any(|c| sql::raw_name(c) == name) {
+                        tracing::warn!(
+                            "{table_name} has no column {name}, it is ignored in event payload"
+                        );
+                    }
+                }
+            }
+            let mut table_columns = tables_columns
+                .iter()
+                .filter(|c| c.table_name.as_ref() == Some(table_name))
+                .collect::<Vec<_>>();
+            table_columns.sort_by_key(|c| c.ordinal);
+            let struct_name = generator.struct_name(table_name);
+            EventPayload {
+                id_newtype: generator.id_newtype(&struct_name, &table_columns).is_some(),
+                struct_name,
+                comment: match table.comment.is_empty() {
+                    true => table.name.clone(),
+                    false => table.comment.clone(),
+                },
+                feature: generator.table_feature(table_name).map(str::to_string),
+                columns,
+            }
+        })
+        .collect()
+}
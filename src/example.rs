@@ -0,0 +1,110 @@
+//! 字段文档中的示例值（--doc-examples）
+//!
+//! 示例值只由表名、列名、类型和长度决定，不读取表中的数据，重复生成时保持不变，不会产生无关的 diff。
+
+use crate::Column;
+
+/// 字符串示例使用的字符
+const CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+/// 字符串示例最多显示的字符数
+const MAX_CHARS: usize = 8;
+
+/// 列的示例值，无法给出示例的类型（二进制、数组等）返回 None
+pub fn example(column: &Column) -> Option<String> {
+    let mut seed = Seed::new(&format!(
+        "{}.{}",
+        column.table_name.as_deref().unwrap_or_default(),
+        column.name.as_deref().unwrap_or_default()
+    ));
+    if !column.enum_values.is_empty() {
+        let value = &column.enum_values[seed.below(column.enum_values.len() as u64) as usize];
+        return Some(format!("{value:?}"));
+    }
+    let example = match column.field_type.as_str() {
+        "bool" => (seed.below(2) == 1).to_string(),
+        "i8" | "u8" => seed.below(100).to_string(),
+        "i16" | "u16" | "i32" | "u32" | "i64" | "u64" => (seed.below(9999) + 1).to_string(),
+        "f32" | "f64" | "bigdecimal::BigDecimal" => {
+            format!("{}.{:02}", seed.below(1000), seed.below(100))
+        }
+        "String" | "CiString" => {
+            let max = column
+                .max_length
+                .and_then(|l| usize::try_from(l).ok())
+                .filter(|l| *l > 0)
+                .unwrap_or(MAX_CHARS);
+            let text = (0..max.min(MAX_CHARS))
+                .map(|_| CHARS[seed.below(CHARS.len() as u64) as usize] as char)
+                .collect::<String>();
+            // 可以更长时用省略号表示
+            let ellipsis = if max > MAX_CHARS { "…" } else { "" };
+            format!("\"{text}{ellipsis}\"")
+        }
+        "time::Date" => date(&mut seed),
+        "time::Time" => time(&mut seed),
+        "time::PrimitiveDateTime" => format!("{}T{}", date(&mut seed), time(&mut seed)),
+        "time::OffsetDateTime" | "UtcDateTime" => {
+            format!("{}T{}Z", date(&mut seed), time(&mut seed))
+        }
+        "uuid::Uuid" => {
+            let hex = format!("{:016x}{:016x}", seed.next(), seed.next());
+            format!(
+                "\"{}-{}-4{}-a{}-{}\"",
+                &hex[0..8],
+                &hex[8..12],
+                &hex[13..16],
+                &hex[17..20],
+                &hex[20..32]
+            )
+        }
+        "serde_json::Value" => "{}".to_string(),
+        "std::net::IpAddr" => format!("\"10.0.{}.{}\"", seed.below(256), seed.below(254) + 1),
+        _ => return None,
+    };
+    Some(example)
+}
+
+fn date(seed: &mut Seed) -> String {
+    format!(
+        "2024-{:02}-{:02}",
+        seed.below(12) + 1,
+        // 所有月份都有 28 天
+        seed.below(28) + 1
+    )
+}
+
+fn time(seed: &mut Seed) -> String {
+    format!(
+        "{:02}:{:02}:{:02}",
+        seed.below(24),
+        seed.below(60),
+        seed.below(60)
+    )
+}
+
+/// 由字符串得到的确定的伪随机数序列（FNV-1a + xorshift）
+struct Seed(u64);
+
+impl Seed {
+    fn new(s: &str) -> Self {
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for byte in s.bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        // xorshift 的状态不能为 0
+        Self(hash | 1)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// [0, n) 之间的数
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+}
@@ -0,0 +1,88 @@
+//! 主键 newtype
+//!
+//! `--id-newtype` 时名为 id 的主键使用 `{结构体}Id` 包装，避免不同表的主键混用，
+//! 附带 Display、FromStr 和 serde（transparent）实现，可直接用于路径参数和查询参数。
+
+use serde::Serialize;
+
+use crate::Column;
+
+/// 可以包装的主键类型
+const INNER_TYPES: [&str; 10] = [
+    "i8",
+    "i16",
+    "i32",
+    "i64",
+    "u8",
+    "u16",
+    "u32",
+    "u64",
+    "String",
+    "uuid::Uuid",
+];
+
+/// 主键 newtype
+#[derive(Serialize, Debug, Clone)]
+pub struct IdNewType {
+    /// Rust 类型名称
+    pub name: String,
+    /// 包装的类型
+    pub inner: String,
+    /// 包装的类型是否实现了 Copy
+    pub copy: bool,
+}
+
+impl IdNewType {
+    /// 不为空、类型可以包装的 id 主键列
+    pub fn new(struct_name: &str, columns: &[&Column]) -> Option<Self> {
+        let column = columns
+            .iter()
+            .find(|c| c.name.as_deref() == Some("id") && c.is_primary_key && !c.is_nullable)?;
+        if !INNER_TYPES.contains(&column.field_type.as_str()) {
+            return None;
+        }
+        Some(Self {
+            name: format!("{struct_name}Id"),
+            inner: column.field_type.clone(),
+            copy: column.field_type != "String",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(name: &str, field_type: &str, is_primary_key: bool) -> Column {
+        Column {
+            name: Some(name.to_string()),
+            field_type: field_type.to_string(),
+            is_primary_key,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn wraps_id_primary_key() {
+        let id = column("id", "i64", true);
+        let name = column("name", "String", false);
+        let newtype = IdNewType::new("UserInfo", &[&id, &name]).unwrap();
+        assert_eq!(newtype.name, "UserInfoId");
+        assert_eq!(newtype.inner, "i64");
+        assert!(newtype.copy);
+
+        let id = column("id", "String", true);
+        assert!(!IdNewType::new("UserInfo", &[&id]).unwrap().copy);
+    }
+
+    #[test]
+    fn skips_other_columns() {
+        // 不是主键、可以为空、不能包装的类型
+        assert!(IdNewType::new("UserInfo", &[&column("id", "i64", false)]).is_none());
+        let mut nullable = column("id", "i64", true);
+        nullable.is_nullable = true;
+        assert!(IdNewType::new("UserInfo", &[&nullable]).is_none());
+        assert!(IdNewType::new("UserInfo", &[&column("id", "Vec<u8>", true)]).is_none());
+        assert!(IdNewType::new("UserInfo", &[&column("user_id", "i64", true)]).is_none());
+    }
+}
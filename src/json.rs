@@ -0,0 +1,48 @@
+//! JSON 转换辅助方法
+//!
+//! `--json-helpers` 时为模型生成 `from_json_value`/`to_json_value`：
+//! 字段名同时支持 snake_case 和 camelCase，字符串形式的数字、布尔值会先转换为对应的类型。
+
+use heck::ToLowerCamelCase;
+use serde::Serialize;
+
+use crate::Column;
+
+/// 单个字段在 JSON 中的名称和类型转换
+#[derive(Serialize, Debug, Clone)]
+pub struct JsonField {
+    /// 反序列化时使用的名称，即 Rust 字段名（去掉 r#）
+    pub field: String,
+    /// 可以接受的 JSON 字段名，已包含引号和转义
+    pub keys: Vec<String>,
+    /// 类型转换：number、boolean，不需要转换时为空
+    pub coerce: Option<&'static str>,
+}
+
+impl JsonField {
+    pub fn new(column: &Column) -> Self {
+        let field = column
+            .name
+            .as_deref()
+            .unwrap_or_default()
+            .trim_start_matches("r#")
+            .to_string();
+        let mut keys = vec![field.clone()];
+        let camel = field.to_lower_camel_case();
+        if camel != field {
+            keys.push(camel);
+        }
+        let coerce = match column.field_type.as_str() {
+            "i8" | "i16" | "i32" | "i64" | "u8" | "u16" | "u32" | "u64" | "f32" | "f64" => {
+                Some("number")
+            }
+            "bool" => Some("boolean"),
+            _ => None,
+        };
+        Self {
+            keys: keys.iter().map(|k| format!("{k:?}")).collect(),
+            field,
+            coerce,
+        }
+    }
+}
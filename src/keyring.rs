@@ -0,0 +1,30 @@
+//! 系统钥匙串中保存的数据库密码
+//!
+//! 按 地址:端口/数据库 保存，服务名为 sqlx-db-cli，通过 keyring 使用系统的凭据存储：
+//! macOS 的钥匙串、Windows 的凭据管理器、Linux 的 Secret Service（GNOME Keyring、KWallet 等），
+//! 密码由系统接口传递，不出现在子进程的参数中。
+
+use ::keyring::{Entry, Error};
+
+/// 钥匙串中的服务名
+const SERVICE: &str = "sqlx-db-cli";
+
+/// 读取保存的密码，没有保存时返回 None
+pub fn get(account: &str) -> anyhow::Result<Option<String>> {
+    match entry(account)?.get_password() {
+        Ok(password) => Ok(Some(password)),
+        Err(Error::NoEntry) => Ok(None),
+        Err(e) => anyhow::bail!("读取钥匙串失败：{e}"),
+    }
+}
+
+/// 保存密码，已有时覆盖
+pub fn set(account: &str, password: &str) -> anyhow::Result<()> {
+    entry(account)?
+        .set_password(password)
+        .map_err(|e| anyhow::anyhow!("写入钥匙串失败：{e}"))
+}
+
+fn entry(account: &str) -> anyhow::Result<Entry> {
+    Entry::new(SERVICE, account).map_err(|e| anyhow::anyhow!("打开钥匙串失败：{e}"))
+}
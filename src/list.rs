@@ -0,0 +1,57 @@
+//! sqlx-db-cli inspect：列出可以生成的表
+//!
+//! 使用与生成时相同的连接参数，输出表名、行数和表注释，加上 `--json` 时输出 JSON 数组，
+//! 行数来自 --table-stats 读取的统计信息（MySQL、PostgreSQL 为估算值）。
+
+use serde::Serialize;
+
+use crate::Table;
+
+/// 输出的一行
+#[derive(Serialize, Debug)]
+struct Row<'a> {
+    name: &'a str,
+    comment: &'a str,
+    rows: Option<i64>,
+}
+
+/// 格式化输出
+pub fn print(tables: &[Table], json: bool) -> anyhow::Result<()> {
+    let rows = tables
+        .iter()
+        .map(|t| Row {
+            name: &t.name,
+            // 没有注释时读取到的注释为表名
+            comment: match t.comment == t.name {
+                true => "",
+                false => t.comment.lines().next().unwrap_or_default(),
+            },
+            rows: t.stats.as_ref().and_then(|s| s.rows),
+        })
+        .collect::<Vec<_>>();
+
+    if json {
+        println!("{:#}", tera::to_value(&rows)?);
+        return Ok(());
+    }
+
+    let rows_text = rows
+        .iter()
+        .map(|r| {
+            r.rows
+                .map(|n| n.to_string())
+                .unwrap_or_else(|| "-".to_string())
+        })
+        .collect::<Vec<_>>();
+    let name_width = rows.iter().map(|r| r.name.len()).max().unwrap_or(0).max(4);
+    let rows_width = rows_text.iter().map(String::len).max().unwrap_or(0).max(4);
+    println!("{:<name_width$}  {:>rows_width$}  comment", "name", "rows");
+    for (row, rows_text) in rows.iter().zip(rows_text.iter()) {
+        let line = format!(
+            "{:<name_width$}  {:>rows_width$}  {}",
+            row.name, rows_text, row.comment
+        );
+        println!("{}", line.trim_end());
+    }
+    Ok(())
+}
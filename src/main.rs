@@ -1,9 +1,74 @@
+use std::process::ExitCode;
+
 use clap::Parser;
-use sqlx_db_cli::Generator;
+use sqlx_db_cli::{
+    cli::{Cli, Command},
+    config, report, Error, OutputFormat,
+};
 
 #[tokio::main]
-async fn main() -> anyhow::Result<()> {
-    let mut gen = Generator::parse();
-    gen.run().await?;
-    Ok(())
+async fn main() -> ExitCode {
+    let args = match config::merge(std::env::args().collect()) {
+        Ok(args) => args,
+        Err(e) => return exit(Err(e)),
+    };
+    let cli = Cli::parse_from(args);
+    cli.init_tracing();
+    let mut gen = match cli.command {
+        // 不指定子命令时等同于 generate
+        None => cli.generator,
+        Some(Command::Generate(gen)) => gen,
+        Some(Command::Check(mut gen)) => {
+            gen.dry_run = true;
+            gen
+        }
+        Some(Command::Diff(mut gen)) => {
+            return match gen.diff().await {
+                Ok(true) => ExitCode::from(1),
+                result => exit(result.map(|_| ())),
+            }
+        }
+        Some(Command::Inspect(mut inspect)) => {
+            return exit(match inspect.table.as_deref() {
+                Some(table) => inspect.generator.describe(table).await,
+                None => inspect.generator.list_tables(inspect.json).await,
+            })
+        }
+        Some(Command::Init(init)) => return exit(init.run()),
+        #[cfg(feature = "serve")]
+        Some(Command::Serve(mut serve)) => return exit(serve.generator.serve(&serve.addr).await),
+        Some(Command::Completions(completions)) => {
+            completions.run();
+            return ExitCode::SUCCESS;
+        }
+    };
+    match gen.run().await {
+        // dry-run 时有变化返回 1，便于脚本判断
+        Ok(changes) if gen.dry_run && !changes.is_empty() => ExitCode::from(1),
+        Ok(_) => ExitCode::SUCCESS,
+        Err(e) => {
+            if gen.output_format == OutputFormat::Json {
+                eprintln!("Error: {e:?}");
+                report::print_error(&e, gen.dry_run);
+                return ExitCode::from(Error::exit_code_of(&e));
+            }
+            if gen.dry_run {
+                eprintln!("Error: {e:?}");
+                println!("dry-run: status=error");
+                return ExitCode::from(Error::exit_code_of(&e));
+            }
+            exit(Err(e))
+        }
+    }
+}
+
+/// 出错时按错误分类返回退出码，见 [`Error::exit_code`]
+fn exit(result: anyhow::Result<()>) -> ExitCode {
+    match result {
+        Ok(_) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {e:?}");
+            ExitCode::from(Error::exit_code_of(&e))
+        }
+    }
 }
@@ -0,0 +1,99 @@
+//! 输出目录中的 MANIFEST.toml：每张表的列对应的 Rust 类型
+//!
+//! 重新生成时与上一次的 MANIFEST.toml 比较，单独列出 Rust 类型有变化的字段（如列改为 BIGINT 后 i32 变为 i64），
+//! 这类变化最容易影响调用方，却混在大量的代码差异中不容易发现。
+
+use std::{collections::BTreeMap, fmt, fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{sql, Column};
+
+/// 清单文件名
+pub const MANIFEST_FILE: &str = "MANIFEST.toml";
+
+/// 表名 → 列名 → Rust 类型（可为空的列为 Option<T>）
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Manifest(pub BTreeMap<String, BTreeMap<String, String>>);
+
+impl Manifest {
+    pub fn collect(columns: &[Column]) -> Self {
+        let mut tables: BTreeMap<String, BTreeMap<String, String>> = BTreeMap::new();
+        for column in columns {
+            let field_type = match column.is_nullable {
+                true => format!("Option<{}>", column.field_type),
+                false => column.field_type.clone(),
+            };
+            tables
+                .entry(column.table_name.clone().unwrap_or_default())
+                .or_default()
+                .insert(sql::raw_name(column).to_string(), field_type);
+        }
+        Self(tables)
+    }
+
+    /// 读取已有的清单，不存在或无法解析时为空
+    pub fn read(path: impl AsRef<Path>) -> Option<Self> {
+        let contents = fs::read_to_string(path).ok()?;
+        Self::parse(&contents)
+    }
+
+    pub fn parse(contents: &str) -> Option<Self> {
+        toml::from_str(contents).ok()
+    }
+
+    /// 只生成部分表时，保留其他表的记录
+    pub fn merge_missing(&mut self, old: Manifest) {
+        for (table, columns) in old.0 {
+            self.0.entry(table).or_insert(columns);
+        }
+    }
+
+    pub fn to_toml(&self) -> anyhow::Result<String> {
+        Ok(format!(
+            "# 由 sqlx-db-cli 生成，记录每列对应的 Rust 类型，重新生成时用于比较类型的变化\n{}",
+            toml::to_string(self)?
+        ))
+    }
+
+    /// 两次都存在、Rust 类型不同的列
+    pub fn type_changes(&self, new: &Manifest) -> Vec<TypeChange> {
+        let mut changes = vec![];
+        for (table, columns) in new.0.iter() {
+            let Some(old_columns) = self.0.get(table) else {
+                continue;
+            };
+            for (column, new_type) in columns.iter() {
+                match old_columns.get(column) {
+                    Some(old_type) if old_type != new_type => changes.push(TypeChange {
+                        table: table.clone(),
+                        column: column.clone(),
+                        old: old_type.clone(),
+                        new: new_type.clone(),
+                    }),
+                    _ => {}
+                }
+            }
+        }
+        changes
+    }
+}
+
+/// Rust 类型有变化的列
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct TypeChange {
+    pub table: String,
+    pub column: String,
+    pub old: String,
+    pub new: String,
+}
+
+impl fmt::Display for TypeChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}.{}: {} -> {}",
+            self.table, self.column, self.old, self.new
+        )
+    }
+}
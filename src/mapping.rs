@@ -0,0 +1,123 @@
+//! 类型映射的后处理
+//!
+//! 各驱动先将数据库类型转换为 Rust 类型，这里再根据生成选项做统一调整。
+
+use crate::{Column, Driver, TimestampPolicy};
+
+/// 三种驱动都支持的类型
+const PORTABLE_TYPES: [&str; 9] = [
+    "bool",
+    "i64",
+    "f64",
+    "String",
+    "Vec<u8>",
+    "time::Date",
+    "time::Time",
+    "time::PrimitiveDateTime",
+    "time::OffsetDateTime",
+];
+
+/// 将列类型限制为三种驱动都支持的类型，返回可能丢失精度或需要转换的说明
+///
+/// sqlx 解码时检查数据库中的类型，改变了类型的列在查询时用 CAST 转换为新类型对应的 SQL 类型
+pub fn portable(column: &mut Column, driver: Driver) -> Option<String> {
+    if PORTABLE_TYPES.contains(&column.field_type.as_str()) {
+        return None;
+    }
+    let (field_type, note) = match column.field_type.as_str() {
+        "i8" | "i16" | "i32" | "u8" | "u16" | "u32" => ("i64", None),
+        "f32" => ("f64", None),
+        // 丢失精度的映射由 lossy 统一说明
+        "u64" => ("i64", None),
+        "bigdecimal::BigDecimal" => ("f64", None),
+        _ => ("String", Some("is not supported by all drivers")),
+    };
+    // 数组、枚举等在 information_schema 中没有类型名称，无法把参数转换回列的类型
+    let read_only =
+        matches!(driver, Driver::Postgres) && crate::sql::writable_type(column).is_none();
+    let warning = note.map(|note| {
+        format!(
+            "{}.{}: {} {note}, mapped to {field_type}{}",
+            column.table_name.as_deref().unwrap_or_default(),
+            column.name.as_deref().unwrap_or_default(),
+            column.field_type,
+            match read_only {
+                true => ", inserting or updating it fails",
+                false => "",
+            },
+        )
+    });
+    column.field_type = field_type.to_string();
+    column.enum_values.clear();
+    column.cast = Some(cast_type(driver, field_type).to_string());
+    warning
+}
+
+/// 可移植类型在查询中转换为的 SQL 类型
+fn cast_type(driver: Driver, field_type: &str) -> &'static str {
+    match (driver, field_type) {
+        (Driver::Mysql, "i64") => "SIGNED",
+        (Driver::Mysql, "f64") => "DOUBLE",
+        (Driver::Mysql, _) => "CHAR",
+        (Driver::Postgres, "i64") => "BIGINT",
+        (Driver::Postgres, "f64") => "DOUBLE PRECISION",
+        (Driver::Postgres, _) => "TEXT",
+        (Driver::Sqlite, "i64") => "INTEGER",
+        (Driver::Sqlite, "f64") => "REAL",
+        (Driver::Sqlite, _) => "TEXT",
+    }
+}
+
+/// 已知会丢失精度的映射的说明：无符号 BIGINT 映射为 i64、NUMERIC 映射为浮点数、
+/// 不带小数秒的 MySQL DATETIME/TIMESTAMP 截断时间中的小数秒
+pub fn lossy(column: &Column, driver: Driver) -> Option<&'static str> {
+    let column_type = column
+        .column_type
+        .as_deref()
+        .unwrap_or_default()
+        .to_lowercase();
+    match column.field_type.as_str() {
+        "i64" if column_type.starts_with("bigint") && column_type.contains("unsigned") => {
+            Some("BIGINT UNSIGNED mapped to i64, values above i64::MAX overflow")
+        }
+        "f32" | "f64"
+            if column_type.starts_with("numeric") || column_type.starts_with("decimal") =>
+        {
+            Some("NUMERIC mapped to a float, may lose precision")
+        }
+        "time::PrimitiveDateTime" | "time::OffsetDateTime" | "UtcDateTime"
+            if matches!(driver, Driver::Mysql)
+                && matches!(column_type.as_str(), "datetime" | "timestamp") =>
+        {
+            Some("stored without fractional seconds, sub-second precision is truncated")
+        }
+        _ => None,
+    }
+}
+
+/// 按 --timestamp-policy 调整时间列的类型，返回无法调整的说明
+///
+/// sqlx 解码时间列时：MySQL DATETIME、PostgreSQL TIMESTAMP 只能解码为 PrimitiveDateTime（MySQL DATETIME 也可以是 OffsetDateTime），
+/// MySQL TIMESTAMP、PostgreSQL TIMESTAMPTZ 只能解码为 OffsetDateTime，Sqlite DATETIME 两者都可以
+pub fn timestamp(column: &mut Column, driver: Driver, policy: TimestampPolicy) -> Option<String> {
+    // Sqlite 的 DATETIME 默认映射为 OffsetDateTime，但并不带时区
+    let naive = column.field_type == "time::PrimitiveDateTime"
+        || (matches!(driver, Driver::Sqlite) && column.field_type == "time::OffsetDateTime");
+    let aware = !naive && column.field_type == "time::OffsetDateTime";
+    match policy {
+        TimestampPolicy::Utc if naive => column.field_type = "UtcDateTime".to_string(),
+        TimestampPolicy::Local | TimestampPolicy::Naive if naive => {
+            column.field_type = "time::PrimitiveDateTime".to_string()
+        }
+        TimestampPolicy::Naive if aware => {
+            return Some(format!(
+                "{}.{}: {} has time zone, kept as time::OffsetDateTime",
+                column.table_name.as_deref().unwrap_or_default(),
+                column.name.as_deref().unwrap_or_default(),
+                column.column_type.as_deref().unwrap_or_default(),
+            ))
+        }
+        _ => {}
+    }
+    None
+}
@@ -0,0 +1,133 @@
+//! 读取表结构前执行 sqlx 迁移
+//!
+//! 默认在同一数据库服务上创建临时数据库（Sqlite 为临时文件）执行迁移并从中读取表结构，
+//! 读取完成后删除，目标数据库不会被修改。
+
+use std::{
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use sqlx::{migrate::Migrator, Connection};
+
+use crate::{version::ServerVersion, Driver};
+
+/// 对指定数据库执行未应用的迁移
+pub async fn run(driver: Driver, url: &str, dir: &str) -> anyhow::Result<()> {
+    let migrator = Migrator::new(Path::new(dir)).await?;
+    match driver {
+        Driver::Mysql => {
+            let mut conn = sqlx::MySqlConnection::connect(url).await?;
+            migrator.run_direct(&mut conn).await?;
+        }
+        Driver::Postgres => {
+            let mut conn = sqlx::PgConnection::connect(url).await?;
+            migrator.run_direct(&mut conn).await?;
+        }
+        Driver::Sqlite => {
+            let mut conn = sqlx::SqliteConnection::connect(url).await?;
+            migrator.run_direct(&mut conn).await?;
+        }
+    }
+    Ok(())
+}
+
+/// 临时数据库
+pub struct TempDatabase {
+    driver: Driver,
+    /// 创建临时数据库时使用的目标数据库连接地址
+    server_url: String,
+    pub name: String,
+    pub url: String,
+}
+
+impl TempDatabase {
+    /// 在目标数据库所在的服务上创建临时数据库
+    pub async fn create(driver: Driver, server_url: &str) -> anyhow::Result<Self> {
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH)?.as_nanos();
+        let name = format!("sqlx_db_cli_{}_{nanos}", std::process::id());
+
+        let url = match driver {
+            Driver::Sqlite => {
+                let path = std::env::temp_dir().join(format!("{name}.sqlite"));
+                format!("sqlite://{}?mode=rwc", path.display())
+            }
+            Driver::Mysql => {
+                let mut conn = sqlx::MySqlConnection::connect(server_url).await?;
+                sqlx::query(&format!("CREATE DATABASE `{name}`"))
+                    .execute(&mut conn)
+                    .await?;
+                with_database(server_url, &name)
+            }
+            Driver::Postgres => {
+                let mut conn = sqlx::PgConnection::connect(server_url).await?;
+                sqlx::query(&format!("CREATE DATABASE \"{name}\""))
+                    .execute(&mut conn)
+                    .await?;
+                with_database(server_url, &name)
+            }
+        };
+
+        Ok(Self {
+            driver,
+            server_url: server_url.to_string(),
+            name,
+            url,
+        })
+    }
+
+    /// 删除临时数据库
+    pub async fn drop(self) -> anyhow::Result<()> {
+        match self.driver {
+            Driver::Sqlite => {
+                std::fs::remove_file(std::env::temp_dir().join(format!("{}.sqlite", self.name)))?;
+            }
+            Driver::Mysql => {
+                let mut conn = sqlx::MySqlConnection::connect(&self.server_url).await?;
+                sqlx::query(&format!("DROP DATABASE `{}`", self.name))
+                    .execute(&mut conn)
+                    .await?;
+            }
+            Driver::Postgres => {
+                let mut conn = sqlx::PgConnection::connect(&self.server_url).await?;
+                let version = sqlx::query_scalar::<_, String>("SHOW server_version_num")
+                    .fetch_one(&mut conn)
+                    .await?;
+                // 读取表结构的连接池可能还未完全关闭，PostgreSQL 13 开始支持 WITH (FORCE)，之前的版本先断开这些连接
+                let force = match ServerVersion::parse_postgres(version.trim().parse()?)
+                    .at_least(13, 0, 0)
+                {
+                    true => " WITH (FORCE)",
+                    false => {
+                        sqlx::query(
+                            "SELECT pg_terminate_backend(pid) FROM pg_stat_activity \
+                             WHERE datname = $1 AND pid <> pg_backend_pid()",
+                        )
+                        .bind(&self.name)
+                        .execute(&mut conn)
+                        .await?;
+                        ""
+                    }
+                };
+                sqlx::query(&format!("DROP DATABASE \"{}\"{force}", self.name))
+                    .execute(&mut conn)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 替换连接地址中的数据库名称，保留连接参数
+fn with_database(url: &str, name: &str) -> String {
+    let (url, params) = match url.split_once('?') {
+        Some((url, params)) => (url, format!("?{params}")),
+        None => (url, String::new()),
+    };
+    match url.rsplit_once('/') {
+        Some((server, _)) if server.contains("://") && !server.ends_with('/') => {
+            format!("{server}/{name}{params}")
+        }
+        _ => format!("{}/{name}{params}", url.trim_end_matches('/')),
+    }
+}
@@ -1,6 +1,11 @@
+use std::collections::HashMap;
+
+use heck::ToUpperCamelCase;
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, Pool};
 
+use crate::version::ServerVersion;
+
 /// +-----------------+--------------------------------------------------------------------+------+-----+---------+-------+
 /// | Field           | Type                                                               | Null | Key | Default | Extra |
 /// +-----------------+--------------------------------------------------------------------+------+-----+---------+-------+
@@ -27,7 +32,7 @@ use sqlx::{FromRow, Pool};
 /// | TABLE_COMMENT   | text                                                               | YES  |     | NULL    |       |
 /// +-----------------+--------------------------------------------------------------------+------+-----+---------+-------+
 #[derive(Default, Debug, Serialize, Deserialize, FromRow)]
-struct Table {
+pub(crate) struct Table {
     table_schema: String,
     table_name: String,
     table_comment: String,
@@ -60,7 +65,7 @@ struct Table {
 /// | SRS_ID                   | int unsigned               | YES  |     | NULL    |       |
 /// +--------------------------+----------------------------+------+-----+---------+-------+
 #[derive(Default, Debug, Serialize, Deserialize, FromRow)]
-struct TableColumn {
+pub(crate) struct TableColumn {
     table_schema: String,
     table_name: String,
     column_name: String,
@@ -73,7 +78,15 @@ struct TableColumn {
     data_type: Option<String>,
     character_maximum_length: Option<i64>,
     column_type: String,
+    /// 索引类型：PRI、UNI、MUL
+    column_key: String,
     column_comment: String,
+    /// 如：auto_increment、on update CURRENT_TIMESTAMP
+    extra: String,
+    /// 生成列的表达式，普通列为空字符串（MariaDB 为 NULL），旧版本没有该列时为 NULL
+    generation_expression: Option<String>,
+    character_set_name: Option<String>,
+    collation_name: Option<String>,
 }
 
 impl From<Table> for super::Table {
@@ -82,18 +95,31 @@ impl From<Table> for super::Table {
             schema: t.table_schema,
             name: t.table_name,
             comment: t.table_comment,
+            ..Default::default()
         }
     }
 }
 
 impl From<TableColumn> for super::Column {
     fn from(c: TableColumn) -> Self {
-        let ty = t2t(&c.column_type.clone().to_uppercase()).to_string();
+        let enum_values = crate::enums::parse_mysql_enum(&c.column_type);
+        let ty = if enum_values.is_empty() {
+            t2t(&c.column_type.clone().to_uppercase()).to_string()
+        } else {
+            format!("{}_{}", c.table_name, c.column_name).to_upper_camel_case()
+        };
         Self {
             schema: Some(c.table_schema.clone()),
             table_name: Some(c.table_name.clone()),
             name: Some(super::column_keywords(c.column_name.clone().as_str())),
+            ordinal: c.ordinal_position.unwrap_or_default(),
             default: c.column_default.clone(),
+            extra: Some(c.extra).filter(|e| !e.is_empty()),
+            // 多行的表达式合并为一行，用于字段文档
+            generation_expression: c
+                .generation_expression
+                .map(|e| e.split_whitespace().collect::<Vec<_>>().join(" "))
+                .filter(|e| !e.is_empty()),
             is_nullable: {
                 if ty.contains("Time") {
                     true
@@ -101,10 +127,21 @@ impl From<TableColumn> for super::Column {
                     c.is_nullable.eq_ignore_ascii_case("yes")
                 }
             },
+            is_primary_key: c.column_key.eq_ignore_ascii_case("pri"),
+            is_indexed: !c.column_key.is_empty(),
             column_type: Some(c.column_type),
             comment: Some(c.column_comment.clone()),
+            charset: c.character_set_name,
+            collation: c.collation_name,
+            enum_values,
+            serde_aliases: vec![],
+            rename: None,
+            attributes: Default::default(),
+            lossy: None,
+            example: None,
+            cast: None,
             field_type: ty,
-            multi_world: Some(c.column_name.clone().contains(|c| c == '_' || c == '-')),
+            multi_world: Some(c.column_name.clone().contains(['_', '-'])),
             max_length: c.character_maximum_length,
         }
     }
@@ -136,10 +173,10 @@ impl From<TableColumn> for super::Column {
 /// uuid::fmt::Hyphenated   CHAR(36)
 /// uuid::fmt::Simple       CHAR(32)
 ///
-/// serde_json::JsonValue  JSON
+/// serde_json::Value      JSON
 ///
 /// Mysql 类型转换为Rust对应类型
-fn t2t(ty: &str) -> &str {
+pub(crate) fn t2t(ty: &str) -> &str {
     match ty.to_uppercase().as_str() {
         "TINYINT(1)" | "BOOLEAN" => "bool",
         "TINYINT" => "i8",
@@ -157,53 +194,227 @@ fn t2t(ty: &str) -> &str {
         "DATE" => "time::Date",
         "TIME" => "time::Time",
         "DATETIME" => "time::PrimitiveDateTime",
-        "TIMESTAMP" => "time::offsetDateTime",
+        "TIMESTAMP" => "time::OffsetDateTime",
         "DECIMAL" => "bigdecimal::BigDecimal",
-        "JSON" => "serde_json:JsonValue",
+        "JSON" => "serde_json::Value",
         _ => "String",
     }
 }
 
-pub async fn tables(
-    pool: &Pool<sqlx::MySql>,
-    table_names: &[&str],
-) -> anyhow::Result<Vec<super::Table>> {
-    let mut sql = "SELECT TABLE_SCHEMA table_schema, TABLE_NAME table_name, TABLE_COMMENT table_comment FROM information_schema.`TABLES` WHERE TABLE_SCHEMA = ( SELECT DATABASE ())"
-        .to_string();
+/// 根据 DDL 中的列类型设置 Rust 类型，与读取 information_schema 时的规则一致
+pub fn retype(column: &mut super::Column, column_type: &str) {
+    let enum_values = crate::enums::parse_mysql_enum(column_type);
+    column.field_type = if enum_values.is_empty() {
+        t2t(&column_type.to_uppercase()).to_string()
+    } else {
+        format!(
+            "{}_{}",
+            column.table_name.as_deref().unwrap_or_default(),
+            column
+                .name
+                .as_deref()
+                .unwrap_or_default()
+                .trim_start_matches("r#")
+        )
+        .to_upper_camel_case()
+    };
+    if column.field_type.contains("Time") {
+        column.is_nullable = true;
+    }
+    column.enum_values = enum_values;
+    column.max_length = crate::ddl::type_length(column_type);
+    column.column_type = Some(column_type.to_string());
+}
 
+/// 查询条件中的库名和表名都使用常量（绑定参数），
+/// 使旧版本 MySQL 能够只扫描指定库的目录而不必打开所有表，减少元数据锁
+fn filter_sql(table_names: &[&str]) -> String {
+    let mut sql = " WHERE TABLE_SCHEMA = ?".to_string();
     if !table_names.is_empty() {
         sql.push_str(&format!(
-            "AND FIND_IN_SET(TABLE_NAME, '{}')",
-            table_names.join(",")
+            " AND TABLE_NAME IN ({})",
+            vec!["?"; table_names.len()].join(",")
         ));
     }
+    sql
+}
 
-    Ok(sqlx::query_as::<_, Table>(&sql)
-        .fetch_all(pool)
+/// 库注释，只有 MariaDB 支持（CREATE DATABASE ... COMMENT），MySQL 没有该列时返回 None
+pub async fn schema_comment(
+    database: &str,
+    pool: &Pool<sqlx::MySql>,
+) -> anyhow::Result<Option<String>> {
+    let comment = sqlx::query_scalar::<_, String>(
+        "SELECT SCHEMA_COMMENT FROM information_schema.SCHEMATA WHERE SCHEMA_NAME = ?",
+    )
+    .bind(database)
+    .fetch_optional(pool)
+    .await
+    .unwrap_or_default();
+    Ok(comment.filter(|c| !c.is_empty()))
+}
+
+pub async fn tables(
+    database: &str,
+    pool: &Pool<sqlx::MySql>,
+    table_names: &[&str],
+) -> anyhow::Result<Vec<super::Table>> {
+    Ok(raw_tables(database, pool, table_names)
         .await?
         .into_iter()
         .map(|t| t.into())
         .collect::<Vec<_>>())
 }
 
+/// information_schema.TABLES 中的原始记录
+pub(crate) async fn raw_tables(
+    database: &str,
+    pool: &Pool<sqlx::MySql>,
+    table_names: &[&str],
+) -> anyhow::Result<Vec<Table>> {
+    let sql = format!(
+        "SELECT TABLE_SCHEMA table_schema, TABLE_NAME table_name, TABLE_COMMENT table_comment FROM information_schema.`TABLES`{}",
+        filter_sql(table_names)
+    );
+
+    let mut query = sqlx::query_as::<_, Table>(&sql).bind(database);
+    for table_name in table_names {
+        query = query.bind(table_name);
+    }
+
+    Ok(query.fetch_all(pool).await?)
+}
+
 pub async fn columns(
+    database: &str,
     pool: &Pool<sqlx::MySql>,
     table_names: &[&str],
+    version: &ServerVersion,
 ) -> anyhow::Result<Vec<super::Column>> {
-    let mut sql = r#"SELECT TABLE_SCHEMA table_schema, TABLE_NAME table_name, COLUMN_NAME column_name, ORDINAL_POSITION ordinal_position, COLUMN_DEFAULT column_default, IS_NULLABLE is_nullable, DATA_TYPE data_type, CHARACTER_MAXIMUM_LENGTH character_maximum_length, COLUMN_TYPE column_type, COLUMN_COMMENT column_comment FROM information_schema.COLUMNS WHERE TABLE_SCHEMA = ( SELECT DATABASE ())"#
-        .to_string();
+    Ok(raw_columns(database, pool, table_names, version)
+        .await?
+        .into_iter()
+        .map(|col| col.into())
+        .collect::<Vec<super::Column>>())
+}
 
-    if !table_names.is_empty() {
-        sql.push_str(&format!(
-            "AND FIND_IN_SET(TABLE_NAME, '{}')",
-            table_names.join(",")
-        ));
+/// information_schema.COLUMNS 中的原始记录
+pub(crate) async fn raw_columns(
+    database: &str,
+    pool: &Pool<sqlx::MySql>,
+    table_names: &[&str],
+    version: &ServerVersion,
+) -> anyhow::Result<Vec<TableColumn>> {
+    // MySQL 5.7.6、MariaDB 10.2.5 之前没有生成列
+    let generation_expression = match version.mariadb {
+        true if version.at_least(10, 2, 5) => "GENERATION_EXPRESSION",
+        false if version.at_least(5, 7, 6) => "GENERATION_EXPRESSION",
+        _ => "NULL",
+    };
+    let sql = format!(
+        "SELECT TABLE_SCHEMA table_schema, TABLE_NAME table_name, COLUMN_NAME column_name, ORDINAL_POSITION ordinal_position, COLUMN_DEFAULT column_default, IS_NULLABLE is_nullable, DATA_TYPE data_type, CHARACTER_MAXIMUM_LENGTH character_maximum_length, COLUMN_TYPE column_type, COLUMN_KEY column_key, COLUMN_COMMENT column_comment, EXTRA extra, {generation_expression} generation_expression, CHARACTER_SET_NAME character_set_name, COLLATION_NAME collation_name FROM information_schema.COLUMNS{} ORDER BY TABLE_NAME, ORDINAL_POSITION",
+        filter_sql(table_names)
+    );
+
+    let mut query = sqlx::query_as::<_, TableColumn>(&sql).bind(database);
+    for table_name in table_names {
+        query = query.bind(table_name);
     }
 
-    Ok(sqlx::query_as::<_, TableColumn>(&sql)
+    Ok(query.fetch_all(pool).await?)
+}
+
+/// 外键引用的表，K：(库名, 表名)，只返回 sources 之外的表
+pub async fn referenced_tables(
+    pool: &Pool<sqlx::MySql>,
+    sources: &[(String, String)],
+) -> anyhow::Result<Vec<(String, String)>> {
+    if sources.is_empty() {
+        return Ok(vec![]);
+    }
+    let sql = format!(
+        "SELECT DISTINCT REFERENCED_TABLE_SCHEMA, REFERENCED_TABLE_NAME FROM information_schema.KEY_COLUMN_USAGE WHERE REFERENCED_TABLE_NAME IS NOT NULL AND (TABLE_SCHEMA, TABLE_NAME) IN ({}) ORDER BY 1, 2",
+        vec!["(?, ?)"; sources.len()].join(",")
+    );
+    let mut query = sqlx::query_as::<_, (String, String)>(&sql);
+    for (schema, table) in sources {
+        query = query.bind(schema).bind(table);
+    }
+    Ok(query
         .fetch_all(pool)
         .await?
         .into_iter()
-        .map(|col| col.into())
-        .collect::<Vec<super::Column>>())
+        .filter(|r| !sources.contains(r))
+        .collect())
+}
+
+/// 表的统计信息，K：表名
+pub async fn stats(
+    database: &str,
+    pool: &Pool<sqlx::MySql>,
+    table_names: &[&str],
+) -> anyhow::Result<HashMap<String, super::TableStats>> {
+    let sql = format!(
+        "SELECT TABLE_NAME, TABLE_ROWS, DATA_LENGTH, AUTO_INCREMENT FROM information_schema.`TABLES`{}",
+        filter_sql(table_names)
+    );
+
+    let mut query =
+        sqlx::query_as::<_, (String, Option<u64>, Option<u64>, Option<u64>)>(&sql).bind(database);
+    for table_name in table_names {
+        query = query.bind(table_name);
+    }
+
+    Ok(query
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(|(table_name, rows, data_length, auto_increment)| {
+            (
+                table_name,
+                super::TableStats {
+                    rows: rows.map(|v| v as i64),
+                    data_length: data_length.map(|v| v as i64),
+                    auto_increment: auto_increment.map(|v| v as i64),
+                },
+            )
+        })
+        .collect())
+}
+
+/// 测试用的表结构样例
+#[cfg(feature = "test-support")]
+pub(crate) fn fixture() -> (Vec<super::Table>, Vec<super::Column>) {
+    let table = Table {
+        table_schema: "fixture".to_string(),
+        table_name: "user".to_string(),
+        table_comment: "用户".to_string(),
+    };
+    let columns = [
+        ("id", "bigint", "NO", "PRI", "主键"),
+        ("name", "varchar(50)", "NO", "", "名称"),
+        ("email", "varchar(255)", "YES", "UNI", "邮箱"),
+        ("amount", "decimal(10,2)", "YES", "", "余额"),
+        ("is_active", "tinyint(1)", "NO", "", "是否启用"),
+        ("created_at", "datetime", "NO", "", "创建时间"),
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(i, (name, ty, nullable, key, comment))| {
+        TableColumn {
+            table_schema: "fixture".to_string(),
+            table_name: "user".to_string(),
+            column_name: name.to_string(),
+            ordinal_position: Some(i as u32 + 1),
+            is_nullable: nullable.to_string(),
+            data_type: ty.split('(').next().map(|t| t.to_string()),
+            column_type: ty.to_string(),
+            column_key: key.to_string(),
+            column_comment: comment.to_string(),
+            ..Default::default()
+        }
+        .into()
+    })
+    .collect();
+    (vec![table.into()], columns)
 }
@@ -0,0 +1,288 @@
+//! 输出目录的锁和整体替换
+//!
+//! 多个 CI 任务或多人同时生成到同一个目录时，文件会交错写入。写入前先获取输出目录的锁，
+//! 锁被占用时等待；全量生成时先在同级的临时目录中写好全部文件，再整体替换输出目录，
+//! 中途失败时输出目录保持原样，重新执行即可。
+//!
+//! 锁文件 `.{目录名}.lock` 放在输出目录的同级目录中，输出目录被整体替换后锁仍然有效，释放锁时删除。
+//! 锁使用 fs4 的文件锁（Unix 为 flock，Windows 为 LockFileEx），各平台都会等待。
+//! 临时目录 `.{目录名}.staging.{进程号}` 的名称带有进程号，其他进程留下的临时目录在获取锁后清理。
+//! 替换时先将输出目录移到 `.{目录名}.old.{进程号}`，再移入临时目录，两步之间中断时输出目录不存在，
+//! 获取锁后从 `.old` 目录恢复。
+
+use std::{
+    fs::{self, File},
+    io,
+    path::{Path, PathBuf},
+};
+
+use fs4::FileExt;
+
+/// 输出目录的锁，drop 时删除锁文件并释放
+pub(crate) struct DirLock {
+    _file: File,
+    path: PathBuf,
+}
+
+impl DirLock {
+    /// 获取锁，被其他进程占用时等待；获取后恢复之前替换时中断移走的输出目录
+    pub(crate) fn acquire(dir: &Path) -> io::Result<Self> {
+        let path = sibling(dir, "lock").unwrap_or_else(|| dir.join(".sqlx-db-cli.lock"));
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        loop {
+            let file = File::options()
+                .create(true)
+                .truncate(false)
+                .write(true)
+                .open(&path)?;
+            match file.try_lock_exclusive() {
+                Ok(()) => {}
+                Err(e) if e.raw_os_error() == fs4::lock_contended_error().raw_os_error() => {
+                    tracing::info!("waiting for lock {}", path.display());
+                    file.lock_exclusive()?;
+                }
+                Err(e) => return Err(e),
+            }
+            // 等待期间持有锁的进程释放时删除了锁文件，锁住的是已删除的文件，重新创建后再获取
+            if is_current(&file, &path) {
+                let lock = Self { _file: file, path };
+                restore(dir)?;
+                return Ok(lock);
+            }
+        }
+    }
+}
+
+impl Drop for DirLock {
+    fn drop(&mut self) {
+        // 先删除，字段 drop 时再关闭文件释放锁，等待中的进程获取锁后会发现文件已删除
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// 打开的文件是否仍然是 path 指向的文件
+#[cfg(unix)]
+fn is_current(file: &File, path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    match (file.metadata(), fs::metadata(path)) {
+        (Ok(opened), Ok(current)) => opened.dev() == current.dev() && opened.ino() == current.ino(),
+        _ => false,
+    }
+}
+
+/// 其他平台上打开的文件不能删除，锁文件始终是同一个
+#[cfg(not(unix))]
+fn is_current(_file: &File, _path: &Path) -> bool {
+    true
+}
+
+/// 全量生成时使用的临时目录，内容为输出目录的副本
+pub(crate) struct Staging {
+    dir: PathBuf,
+    staging: PathBuf,
+    old: PathBuf,
+}
+
+impl Staging {
+    /// 复制输出目录，需要先获取锁；
+    /// 输出目录为当前目录等没有目录名的路径、符号链接，或者含有子目录（不只是生成的代码）时返回 None，不整体替换
+    pub(crate) fn new(dir: &Path) -> io::Result<Option<Self>> {
+        if !Self::replaces(dir)? {
+            return Ok(None);
+        }
+        Self::copy(dir).map(Some)
+    }
+
+    /// 全量生成时是否整体替换输出目录，见 [`Staging::new`]
+    pub(crate) fn replaces(dir: &Path) -> io::Result<bool> {
+        let symlink = fs::symlink_metadata(dir).is_ok_and(|m| m.file_type().is_symlink());
+        Ok(dir.file_name().is_some() && !symlink && !has_subdir(dir)?)
+    }
+
+    /// --staging 时使用，子目录一起复制；输出目录没有目录名或者是符号链接时无法整体替换，返回错误
+    pub(crate) fn whole(dir: &Path) -> io::Result<Self> {
+        let symlink = fs::symlink_metadata(dir).is_ok_and(|m| m.file_type().is_symlink());
+        if dir.file_name().is_none() || symlink {
+            return Err(io::Error::other(format!(
+                "{} 没有目录名或者是符号链接，无法整体替换",
+                dir.display()
+            )));
+        }
+        Self::copy(dir)
+    }
+
+    /// 清理之前中断留下的临时目录，再复制输出目录，需要先获取锁
+    fn copy(dir: &Path) -> io::Result<Self> {
+        let suffix = std::process::id();
+        let (Some(staging), Some(old)) = (
+            sibling(dir, &format!("staging.{suffix}")),
+            sibling(dir, &format!("old.{suffix}")),
+        ) else {
+            return Err(io::Error::other(format!("{} 没有目录名", dir.display())));
+        };
+        remove_stale(dir)?;
+        copy_dir(dir, &staging)?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            staging,
+            old,
+        })
+    }
+
+    /// 文件是否在输出目录中
+    pub(crate) fn contains(&self, path: &Path) -> bool {
+        path.starts_with(&self.dir)
+    }
+
+    /// 输出目录中的文件在临时目录中的路径
+    pub(crate) fn path(&self, path: &str) -> PathBuf {
+        match Path::new(path).strip_prefix(&self.dir) {
+            Ok(relative) => self.staging.join(relative),
+            Err(_) => PathBuf::from(path),
+        }
+    }
+
+    /// 用临时目录替换输出目录
+    pub(crate) fn commit(self) -> io::Result<()> {
+        if self.dir.exists() {
+            fs::rename(&self.dir, &self.old)?;
+        }
+        fs::rename(&self.staging, &self.dir)?;
+        if self.old.exists() {
+            fs::remove_dir_all(&self.old)?;
+        }
+        Ok(())
+    }
+
+    /// 放弃临时目录
+    pub(crate) fn discard(self) {
+        let _ = fs::remove_dir_all(&self.staging);
+    }
+}
+
+/// 与输出目录同级的 .{目录名}.{suffix}
+fn sibling(dir: &Path, suffix: &str) -> Option<PathBuf> {
+    let name = dir.file_name()?.to_string_lossy();
+    let parent = dir.parent().unwrap_or(Path::new(""));
+    Some(parent.join(format!(".{name}.{suffix}")))
+}
+
+/// 持有锁时其他进程不会使用临时目录，存在时为之前中断留下的
+fn remove_stale(dir: &Path) -> io::Result<()> {
+    for path in stale(dir)? {
+        fs::remove_dir_all(path)?;
+    }
+    Ok(())
+}
+
+/// 之前中断留下的临时目录，整体替换输出目录前删除；dry-run 时只列出。
+/// 用于恢复输出目录的 .old 目录不在其中，见 [`interrupted`]
+pub(crate) fn stale(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let restored = interrupted(dir)?;
+    Ok(leftovers(dir)?
+        .into_iter()
+        .filter(|p| Some(p) != restored.as_ref())
+        .collect())
+}
+
+/// 替换时在两次重命名之间中断，输出目录不存在而留下了 .old 目录，返回其中最新的一个
+pub(crate) fn interrupted(dir: &Path) -> io::Result<Option<PathBuf>> {
+    let Some(name) = dir.file_name().map(|n| n.to_string_lossy().to_string()) else {
+        return Ok(None);
+    };
+    if dir.exists() {
+        return Ok(None);
+    }
+    let prefix = format!(".{name}.old");
+    let mut newest = None;
+    for path in leftovers(dir)? {
+        let is_old = path
+            .file_name()
+            .is_some_and(|n| n.to_string_lossy().starts_with(&prefix));
+        if !is_old {
+            continue;
+        }
+        let modified = fs::metadata(&path)?.modified()?;
+        if newest.as_ref().is_none_or(|(time, _)| modified > *time) {
+            newest = Some((modified, path));
+        }
+    }
+    Ok(newest.map(|(_, path)| path))
+}
+
+/// 从 .old 目录恢复中断时移走的输出目录，需要先获取锁
+fn restore(dir: &Path) -> io::Result<()> {
+    if let Some(old) = interrupted(dir)? {
+        tracing::warn!(
+            "{} is missing after an interrupted run, restored from {}",
+            dir.display(),
+            old.display()
+        );
+        fs::rename(old, dir)?;
+    }
+    Ok(())
+}
+
+/// 同级目录中的 .{目录名}.staging*、.{目录名}.old* 目录，按名称排序
+fn leftovers(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let Some(name) = dir.file_name().map(|n| n.to_string_lossy().to_string()) else {
+        return Ok(vec![]);
+    };
+    let parent = match dir.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => Path::new("."),
+    };
+    if !parent.exists() {
+        return Ok(vec![]);
+    }
+    let prefixes = [format!(".{name}.staging"), format!(".{name}.old")];
+    let mut paths = vec![];
+    for entry in fs::read_dir(parent)? {
+        let entry = entry?;
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        let stale = prefixes.iter().any(|p| {
+            file_name
+                .strip_prefix(p.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+        });
+        if stale && entry.file_type()?.is_dir() {
+            paths.push(entry.path());
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+/// 复制目录，源目录不存在时创建空目录
+fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    if !from.exists() {
+        return Ok(());
+    }
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        match entry.file_type()?.is_dir() {
+            true => copy_dir(&entry.path(), &to.join(entry.file_name()))?,
+            false => {
+                fs::copy(entry.path(), to.join(entry.file_name()))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 目录中是否有子目录
+fn has_subdir(dir: &Path) -> io::Result<bool> {
+    if !dir.exists() {
+        return Ok(false);
+    }
+    for entry in fs::read_dir(dir)? {
+        if entry?.file_type()?.is_dir() {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
@@ -0,0 +1,211 @@
+//! 生成流水线
+//!
+//! 生成分为五个阶段：Introspect（读取表结构）→ Map（映射类型）→ Filter（过滤表和列）→ Render（渲染代码）→ Write（写入文件）。
+//! 每个阶段是一个方法带默认实现的 trait，作为库使用时可以替换 Introspect、Render、Write，追加 Map、Filter，
+//! 再用 [`Generator::generate_with`] 执行，例如：
+//!
+//! ```ignore
+//! struct DropAudit;
+//!
+//! impl Filter for DropAudit {
+//!     fn filter(&self, _: &Generator, _: &mut Vec<Table>, columns: &mut Vec<Column>) -> anyhow::Result<()> {
+//!         columns.retain(|c| c.name.as_deref() != Some("audit_log"));
+//!         Ok(())
+//!     }
+//! }
+//!
+//! let pipeline = Pipeline::default().add_filter(DropAudit);
+//! generator.generate_with(&pipeline).await?;
+//! ```
+
+use async_trait::async_trait;
+
+use crate::{Changes, Column, Generator, Table};
+
+/// 读取表结构
+#[async_trait]
+pub trait Introspect: Send + Sync {
+    /// 默认从数据库读取，指定 --migrate-dir、--from-ddl 时从迁移后的数据库、DDL 文件读取
+    async fn introspect(&self, generator: &Generator) -> anyhow::Result<(Vec<Table>, Vec<Column>)> {
+        generator.read_schema().await
+    }
+}
+
+/// 映射列的 Rust 类型，返回需要提示的警告
+pub trait Map: Send + Sync {
+    /// 默认按驱动和类型选项映射，见 [`Generator::map_types`]
+    fn map(&self, generator: &Generator, columns: &mut [Column]) -> anyhow::Result<Vec<String>> {
+        generator.map_types(columns)
+    }
+}
+
+/// 过滤表和列
+pub trait Filter: Send + Sync {
+    /// 默认不过滤
+    fn filter(
+        &self,
+        generator: &Generator,
+        tables: &mut Vec<Table>,
+        columns: &mut Vec<Column>,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+/// 渲染代码
+pub trait Render: Send + Sync {
+    /// 默认使用内置模板渲染，见 [`Generator::render_files`]
+    fn render(
+        &self,
+        generator: &Generator,
+        tables: Vec<Table>,
+        columns: Vec<Column>,
+    ) -> anyhow::Result<Rendered> {
+        generator.render_files(tables, columns)
+    }
+}
+
+/// 写入渲染结果
+#[async_trait]
+pub trait Write: Send + Sync {
+    /// 默认写入本地文件，见 [`Generator::write_rendered`]
+    async fn write(&self, generator: &Generator, rendered: Rendered) -> anyhow::Result<Changes> {
+        generator.write_rendered(rendered)
+    }
+}
+
+/// 使用默认实现的阶段
+pub struct Defaults;
+
+impl Introspect for Defaults {}
+impl Map for Defaults {}
+impl Filter for Defaults {}
+impl Render for Defaults {}
+impl Write for Defaults {}
+
+/// 渲染得到的文件
+#[derive(Debug, Clone)]
+pub struct OutputFile {
+    pub path: String,
+    pub contents: String,
+}
+
+/// 输出目录及其中的文件，写入时持有目录的锁
+#[derive(Debug, Clone)]
+pub struct OutputDir {
+    pub path: String,
+    pub files: Vec<OutputFile>,
+}
+
+/// 渲染结果
+#[derive(Debug, Clone, Default)]
+pub struct Rendered {
+    /// 不属于输出目录的文件，如 --crate-target 的 Cargo.toml、lib.rs
+    pub files: Vec<OutputFile>,
+    pub dirs: Vec<OutputDir>,
+    /// 渲染失败的表，其他表的文件仍然写入
+    pub failed: Vec<String>,
+}
+
+/// 生成流水线，Map、Filter 按添加的顺序执行
+pub struct Pipeline {
+    introspect: Box<dyn Introspect>,
+    maps: Vec<Box<dyn Map>>,
+    filters: Vec<Box<dyn Filter>>,
+    render: Box<dyn Render>,
+    write: Box<dyn Write>,
+}
+
+impl Default for Pipeline {
+    fn default() -> Self {
+        Self {
+            introspect: Box::new(Defaults),
+            maps: vec![Box::new(Defaults)],
+            filters: vec![],
+            render: Box::new(Defaults),
+            write: Box::new(Defaults),
+        }
+    }
+}
+
+impl Pipeline {
+    /// 替换 Introspect 阶段
+    pub fn with_introspect(mut self, stage: impl Introspect + 'static) -> Self {
+        self.introspect = Box::new(stage);
+        self
+    }
+
+    /// 在默认的类型映射之后追加 Map 阶段
+    pub fn add_map(mut self, stage: impl Map + 'static) -> Self {
+        self.maps.push(Box::new(stage));
+        self
+    }
+
+    /// 追加 Filter 阶段
+    pub fn add_filter(mut self, stage: impl Filter + 'static) -> Self {
+        self.filters.push(Box::new(stage));
+        self
+    }
+
+    /// 替换 Render 阶段
+    pub fn with_render(mut self, stage: impl Render + 'static) -> Self {
+        self.render = Box::new(stage);
+        self
+    }
+
+    /// 替换 Write 阶段
+    pub fn with_write(mut self, stage: impl Write + 'static) -> Self {
+        self.write = Box::new(stage);
+        self
+    }
+
+    pub(crate) async fn introspect(
+        &self,
+        generator: &Generator,
+    ) -> anyhow::Result<(Vec<Table>, Vec<Column>)> {
+        self.introspect.introspect(generator).await
+    }
+
+    /// 依次执行 Map 阶段，返回所有警告
+    pub(crate) fn map(
+        &self,
+        generator: &Generator,
+        columns: &mut [Column],
+    ) -> anyhow::Result<Vec<String>> {
+        let mut warnings = vec![];
+        for stage in self.maps.iter() {
+            warnings.extend(stage.map(generator, columns)?);
+        }
+        Ok(warnings)
+    }
+
+    /// 依次执行 Filter 阶段
+    pub(crate) fn filter(
+        &self,
+        generator: &Generator,
+        tables: &mut Vec<Table>,
+        columns: &mut Vec<Column>,
+    ) -> anyhow::Result<()> {
+        for stage in self.filters.iter() {
+            stage.filter(generator, tables, columns)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn render(
+        &self,
+        generator: &Generator,
+        tables: Vec<Table>,
+        columns: Vec<Column>,
+    ) -> anyhow::Result<Rendered> {
+        self.render.render(generator, tables, columns)
+    }
+
+    pub(crate) async fn write(
+        &self,
+        generator: &Generator,
+        rendered: Rendered,
+    ) -> anyhow::Result<Changes> {
+        self.write.write(generator, rendered).await
+    }
+}
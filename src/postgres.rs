@@ -1,16 +1,32 @@
+use std::collections::HashMap;
+
+use heck::ToUpperCamelCase;
+use lazy_static::lazy_static;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, Pool};
 
+use crate::version::ServerVersion;
+
+lazy_static! {
+    /// 类型名称中的长度、精度，如：(50)、(10, 2)
+    static ref TYPE_MODIFIER: Regex = Regex::new(r"\(\s*\d+(\s*,\s*\d+)?\s*\)").unwrap();
+}
+
 #[derive(Default, Debug, Serialize, Deserialize, FromRow)]
-struct Table {
+pub(crate) struct Table {
     table_catalog: String,
     table_schema: String,
     table_name: String,
     description: Option<String>,
+    is_partition: bool,
+    partition_of: Option<String>,
+    partition_key: Option<String>,
+    inherits: Vec<String>,
 }
 
 #[derive(Default, Debug, Serialize, Deserialize, FromRow)]
-struct TableColumn {
+pub(crate) struct TableColumn {
     table_catalog: String,
     table_schema: String,
     table_name: String,
@@ -19,7 +35,11 @@ struct TableColumn {
     column_default: Option<String>,
     is_nullable: String,
     data_type: String,
+    domain_name: Option<String>,
     character_maximum_length: Option<i32>,
+    is_primary_key: bool,
+    is_indexed: bool,
+    collation: Option<String>,
     description: Option<String>,
 }
 
@@ -29,6 +49,15 @@ impl From<Table> for super::Table {
             schema: t.table_schema,
             name: t.table_name.clone(),
             comment: t.description.unwrap_or(t.table_name),
+            is_partition: t.is_partition,
+            partition_of: t.partition_of,
+            partition_key: t.partition_key,
+            inherits: t.inherits,
+            stats: None,
+            schema_comment: None,
+            constraints: vec![],
+            deprecated: None,
+            query: None,
         }
     }
 }
@@ -40,7 +69,10 @@ impl From<TableColumn> for super::Column {
             schema: Some(c.table_schema.clone()),
             table_name: Some(c.table_name.clone()),
             name: Some(super::column_keywords(c.column_name.clone().as_str())),
+            ordinal: c.ordinal_position as u32,
             default: c.column_default.clone(),
+            extra: None,
+            generation_expression: None,
             is_nullable: {
                 if ty.contains("Time") {
                     true
@@ -48,10 +80,22 @@ impl From<TableColumn> for super::Column {
                     c.is_nullable.eq_ignore_ascii_case("yes")
                 }
             },
-            column_type: Some(c.data_type),
+            is_primary_key: c.is_primary_key,
+            is_indexed: c.is_indexed,
+            // 域使用域名，以便按 --type-map 映射
+            column_type: Some(c.domain_name.unwrap_or(c.data_type)),
             comment: c.description,
+            charset: None,
+            collation: c.collation,
+            enum_values: vec![],
+            serde_aliases: vec![],
+            rename: None,
+            attributes: Default::default(),
+            lossy: None,
+            example: None,
+            cast: None,
             field_type: ty,
-            multi_world: Some(c.column_name.clone().contains(|c| c == '_' || c == '-')),
+            multi_world: Some(c.column_name.clone().contains(['_', '-'])),
             max_length: {
                 if let Some(l) = c.character_maximum_length {
                     Some(l as i64)
@@ -100,7 +144,7 @@ impl From<TableColumn> for super::Column {
 /// serde_json::Value       JSON, JSONB
 ///
 /// PostgreSQL 类型转换为Rust对应类型
-fn t2t(ty: &str) -> &str {
+pub(crate) fn t2t(ty: &str) -> &str {
     match ty.to_uppercase().as_str() {
         "BOOL" => "bool",
         "CHAR" => "i8",
@@ -125,7 +169,7 @@ fn t2t(ty: &str) -> &str {
         "TIMESTAMPTZ" => "time::OffsetDateTime",
         "TIMETZ" => "sqlx_postgres::types::PgTimeTz",
         "NUMERIC" => "bigdecimal::BigDecimal",
-        "JSON" | "JSONB" => "serde_json:JsonValue",
+        "JSON" | "JSONB" => "serde_json::Value",
         "UUID" => "uuid::Uuid",
         "INET" | "CIDR" => "std::net::IpAddr",
         "MACADDR" => "mac_address::MacAddress",
@@ -134,35 +178,108 @@ fn t2t(ty: &str) -> &str {
     }
 }
 
+/// 库和 public 模式的注释
+pub async fn schema_comment(pool: &Pool<sqlx::Postgres>) -> anyhow::Result<Option<String>> {
+    let (database, schema) = sqlx::query_as::<_, (Option<String>, Option<String>)>(
+        "SELECT shobj_description(d.oid, 'pg_database'), obj_description(n.oid, 'pg_namespace') FROM pg_database d, pg_namespace n WHERE d.datname = current_database() AND n.nspname = 'public'",
+    )
+    .fetch_optional(pool)
+    .await?
+    .unwrap_or_default();
+    Ok(join_schema_comment(database, schema))
+}
+
+/// 合并库注释和模式注释，忽略 public 模式默认的注释
+pub fn join_schema_comment(database: Option<String>, schema: Option<String>) -> Option<String> {
+    let comments = [database, schema]
+        .into_iter()
+        .flatten()
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty() && c != "standard public schema")
+        .collect::<Vec<_>>();
+    (!comments.is_empty()).then(|| comments.join("\n\n"))
+}
+
 pub async fn tables(
     database: &str,
+    schema: &str,
     pool: &Pool<sqlx::Postgres>,
     table_names: &[&str],
+    version: &ServerVersion,
 ) -> anyhow::Result<Vec<super::Table>> {
-    let mut sql = format!("SELECT tb.table_catalog, tb.table_schema, tb.TABLE_NAME, d.description FROM information_schema.tables tb JOIN pg_class C ON C.relname = tb. TABLE_NAME LEFT JOIN pg_description d ON d.objoid = C.OID  AND d.objsubid = '0' WHERE tb.table_catalog = '{database}' and tb.table_schema = 'public' ");
-
-    if !table_names.is_empty() {
-        sql.push_str(&format!(
-            "and tb.table_name in ('{}')",
-            table_names.join(",")
-        ));
-    }
-
-    Ok(sqlx::query_as::<_, Table>(&sql)
-        .fetch_all(pool)
+    Ok(raw_tables(database, schema, pool, table_names, version)
         .await?
         .into_iter()
         .map(|t| t.into())
         .collect::<Vec<_>>())
 }
 
-pub async fn columns(
+/// information_schema.tables 中的原始记录，带有表注释和分区、继承信息
+pub(crate) async fn raw_tables(
     database: &str,
+    schema: &str,
     pool: &Pool<sqlx::Postgres>,
     table_names: &[&str],
-) -> anyhow::Result<Vec<super::Column>> {
+    version: &ServerVersion,
+) -> anyhow::Result<Vec<Table>> {
+    // 10 之前没有分区表
+    let (is_partition, partition_key) = match version.at_least(10, 0, 0) {
+        true => ("C.relispartition", "pg_get_partkeydef ( C.OID )"),
+        false => ("FALSE", "NULL::TEXT"),
+    };
     let mut sql = format!(
         "
+SELECT
+	tb.table_catalog,
+	tb.table_schema,
+	tb.TABLE_NAME,
+	d.description,
+	{is_partition} AS is_partition,
+	( SELECT P.relname::TEXT FROM pg_inherits i JOIN pg_class P ON P.OID = i.inhparent WHERE i.inhrelid = C.OID AND {is_partition} LIMIT 1 ) AS partition_of,
+	{partition_key} AS partition_key,
+	ARRAY ( SELECT P.relname::TEXT FROM pg_inherits i JOIN pg_class P ON P.OID = i.inhparent WHERE i.inhrelid = C.OID AND NOT {is_partition} ORDER BY i.inhseqno ) AS inherits
+FROM
+	information_schema.tables tb
+	JOIN pg_namespace n ON n.nspname = tb.table_schema
+	JOIN pg_class C ON C.relname = tb.TABLE_NAME AND C.relnamespace = n.OID
+	LEFT JOIN pg_description d ON d.objoid = C.OID
+	AND d.objsubid = '0'
+WHERE
+	tb.table_catalog = $1
+	AND tb.table_schema = $2
+"
+    );
+
+    if !table_names.is_empty() {
+        sql.push_str(" AND tb.table_name = ANY($3)");
+    }
+
+    let mut query = sqlx::query_as::<_, Table>(&sql).bind(database).bind(schema);
+    if !table_names.is_empty() {
+        query = query.bind(table_names);
+    }
+    Ok(query.fetch_all(pool).await?)
+}
+
+pub async fn columns(
+    database: &str,
+    schema: &str,
+    pool: &Pool<sqlx::Postgres>,
+    table_names: &[&str],
+) -> anyhow::Result<Vec<super::Column>> {
+    let enum_types = enum_types(pool).await?;
+    let columns = raw_columns(database, schema, pool, table_names).await?;
+    Ok(convert_columns(columns, &enum_types))
+}
+
+/// information_schema.COLUMNS 中的原始记录，带有主键、索引、排序规则和列注释
+pub(crate) async fn raw_columns(
+    database: &str,
+    schema: &str,
+    pool: &Pool<sqlx::Postgres>,
+    table_names: &[&str],
+) -> anyhow::Result<Vec<TableColumn>> {
+    let mut sql = "
 SELECT
 	col.table_catalog,
 	col.table_schema,
@@ -172,24 +289,45 @@ SELECT
 	col.column_default,
 	col.is_nullable,
 	col.udt_name as data_type,
+	col.domain_name::TEXT,
 	col.character_maximum_length,
+	EXISTS (
+		SELECT 1 FROM information_schema.table_constraints tc
+		JOIN information_schema.key_column_usage kcu ON kcu.constraint_name = tc.constraint_name
+		AND kcu.table_schema = tc.table_schema
+		WHERE tc.constraint_type = 'PRIMARY KEY'
+		AND kcu.table_schema = col.table_schema
+		AND kcu.table_name = col.table_name
+		AND kcu.column_name = col.column_name
+	) AS is_primary_key,
+	EXISTS (
+		SELECT 1 FROM pg_index i
+		JOIN pg_attribute A ON A.attrelid = i.indrelid AND A.attnum = i.indkey[0]
+		WHERE i.indrelid = C.OID
+		AND A.attname = col.column_name
+	) AS is_indexed,
+	(
+		SELECT co.collname::TEXT FROM pg_attribute A
+		JOIN pg_collation co ON co.OID = A.attcollation
+		WHERE A.attrelid = C.OID
+		AND A.attname = col.column_name
+		AND co.collname <> 'default'
+	) AS collation,
 	d.description 
 FROM
 	information_schema.COLUMNS col
-	JOIN pg_class C ON C.relname = col.
-	TABLE_NAME LEFT JOIN pg_description d ON d.objoid = C.OID 
+	JOIN pg_namespace n ON n.nspname = col.table_schema
+	JOIN pg_class C ON C.relname = col.TABLE_NAME AND C.relnamespace = n.OID
+	LEFT JOIN pg_description d ON d.objoid = C.OID 
 	AND d.objsubid = col.ordinal_position 
 WHERE
-	col.table_catalog = '{database}' 
-	AND col.table_schema = 'public' 
+	col.table_catalog = $1
+	AND col.table_schema = $2
 "
-    );
+    .to_string();
 
     if !table_names.is_empty() {
-        sql.push_str(&format!(
-            " and col.table_name in ('{}')",
-            table_names.join(",")
-        ));
+        sql.push_str(" AND col.table_name = ANY($3)");
     }
 
     sql.push_str(
@@ -198,10 +336,242 @@ WHERE
 	col.ordinal_position;",
     );
 
-    Ok(sqlx::query_as::<_, TableColumn>(&sql)
+    let mut query = sqlx::query_as::<_, TableColumn>(&sql)
+        .bind(database)
+        .bind(schema);
+    if !table_names.is_empty() {
+        query = query.bind(table_names);
+    }
+    Ok(query.fetch_all(pool).await?)
+}
+
+/// 原始记录转换为列，枚举类型的列使用枚举名
+pub(crate) fn convert_columns(
+    columns: Vec<TableColumn>,
+    enum_types: &HashMap<String, Vec<String>>,
+) -> Vec<super::Column> {
+    columns
+        .into_iter()
+        .map(|col| {
+            let mut col: super::Column = col.into();
+            if let Some(values) = enum_types.get(col.column_type.as_deref().unwrap_or_default()) {
+                col.field_type = col.column_type.clone().unwrap().to_upper_camel_case();
+                col.enum_values = values.clone();
+            }
+            col
+        })
+        .collect::<Vec<super::Column>>()
+}
+
+/// 根据 DDL 中的列类型设置 Rust 类型，与读取 information_schema 时的规则一致
+pub fn retype(
+    column: &mut super::Column,
+    column_type: &str,
+    enum_types: &HashMap<String, Vec<String>>,
+) {
+    let udt_name = udt_name(column_type);
+    match enum_types.get(&udt_name) {
+        Some(values) => {
+            column.field_type = udt_name.to_upper_camel_case();
+            column.enum_values = values.clone();
+        }
+        None => column.field_type = t2t(&udt_name.to_uppercase()).to_string(),
+    }
+    if column.field_type.contains("Time") {
+        column.is_nullable = true;
+    }
+    column.max_length = crate::ddl::type_length(column_type).or(Some(50));
+    column.column_type = Some(udt_name);
+}
+
+/// DDL 中的类型名称转换为 udt_name，如：character varying(50) => varchar，integer[] => _int4
+fn udt_name(column_type: &str) -> String {
+    let lower = column_type.to_lowercase();
+    let (base, is_array) = match lower.strip_suffix("[]") {
+        Some(base) => (base, true),
+        None => (lower.as_str(), false),
+    };
+    let base = TYPE_MODIFIER.replace_all(base, "");
+    let base = base.split_whitespace().collect::<Vec<_>>().join(" ");
+    // 带模式前缀的自定义类型，如：public.mood
+    let base = base
+        .rsplit('.')
+        .next()
+        .unwrap_or_default()
+        .trim_matches('"');
+    let udt_name = match base {
+        "integer" | "int" | "serial" => "int4",
+        "bigint" | "bigserial" => "int8",
+        "smallint" | "smallserial" => "int2",
+        "boolean" => "bool",
+        "real" => "float4",
+        "double precision" => "float8",
+        "character varying" => "varchar",
+        "character" | "char" => "bpchar",
+        "timestamp without time zone" => "timestamp",
+        "timestamp with time zone" => "timestamptz",
+        "time without time zone" => "time",
+        "time with time zone" => "timetz",
+        "decimal" => "numeric",
+        "bit varying" => "varbit",
+        other => other,
+    };
+    if is_array {
+        format!("_{udt_name}")
+    } else {
+        udt_name.to_string()
+    }
+}
+
+/// 表的统计信息，K：表名
+pub async fn stats(
+    schema: &str,
+    pool: &Pool<sqlx::Postgres>,
+    table_names: &[&str],
+) -> anyhow::Result<HashMap<String, super::TableStats>> {
+    let mut sql = "SELECT C.relname::TEXT, C.reltuples::BIGINT, pg_relation_size(C.OID) FROM pg_class C JOIN pg_namespace n ON n.OID = C.relnamespace WHERE n.nspname = $1 AND C.relkind IN ('r', 'p')".to_string();
+
+    if !table_names.is_empty() {
+        sql.push_str(" AND C.relname = ANY($2)");
+    }
+
+    let mut query = sqlx::query_as::<_, (String, i64, i64)>(&sql).bind(schema);
+    if !table_names.is_empty() {
+        query = query.bind(table_names);
+    }
+    Ok(query
         .fetch_all(pool)
         .await?
         .into_iter()
-        .map(|col| col.into())
-        .collect::<Vec<super::Column>>())
+        .map(|(table_name, rows, data_length)| {
+            (
+                table_name,
+                super::TableStats {
+                    // 从未 ANALYZE 过的表为 -1
+                    rows: (rows >= 0).then_some(rows),
+                    data_length: Some(data_length),
+                    auto_increment: None,
+                },
+            )
+        })
+        .collect())
+}
+
+/// 表的唯一约束和排除约束，K：表名
+pub async fn constraints(
+    schema: &str,
+    pool: &Pool<sqlx::Postgres>,
+    table_names: &[&str],
+) -> anyhow::Result<HashMap<String, Vec<super::Constraint>>> {
+    let mut sql = "SELECT C.relname::TEXT, con.conname::TEXT, con.contype::TEXT, ARRAY(SELECT A.attname::TEXT FROM unnest(con.conkey) WITH ORDINALITY k(attnum, ord) JOIN pg_attribute A ON A.attrelid = con.conrelid AND A.attnum = k.attnum ORDER BY k.ord), pg_get_constraintdef(con.OID), con.condeferrable, con.condeferred FROM pg_constraint con JOIN pg_class C ON C.OID = con.conrelid JOIN pg_namespace n ON n.OID = C.relnamespace WHERE n.nspname = $1 AND con.contype IN ('u', 'x')".to_string();
+
+    if !table_names.is_empty() {
+        sql.push_str(" AND C.relname = ANY($2)");
+    }
+    sql.push_str(" ORDER BY C.relname, con.conname");
+
+    let mut query =
+        sqlx::query_as::<_, (String, String, String, Vec<String>, String, bool, bool)>(&sql)
+            .bind(schema);
+    if !table_names.is_empty() {
+        query = query.bind(table_names);
+    }
+    let mut constraints: HashMap<String, Vec<super::Constraint>> = HashMap::new();
+    for (table_name, name, kind, columns, definition, deferrable, initially_deferred) in
+        query.fetch_all(pool).await?
+    {
+        constraints
+            .entry(table_name)
+            .or_default()
+            .push(super::Constraint {
+                name,
+                kind: match kind.as_str() {
+                    "x" => "exclusion",
+                    _ => "unique",
+                }
+                .to_string(),
+                columns,
+                definition,
+                deferrable,
+                initially_deferred,
+            });
+    }
+    Ok(constraints)
+}
+
+/// 外键引用的表，K：(模式, 表名)，只返回 sources 之外的表
+pub async fn referenced_tables(
+    pool: &Pool<sqlx::Postgres>,
+    sources: &[(String, String)],
+) -> anyhow::Result<Vec<(String, String)>> {
+    let (schemas, names): (Vec<_>, Vec<_>) = sources.iter().cloned().unzip();
+    let rows = sqlx::query_as::<_, (String, String)>(
+        "SELECT DISTINCT fn.nspname::TEXT, fc.relname::TEXT FROM pg_constraint con JOIN pg_class C ON C.OID = con.conrelid JOIN pg_namespace n ON n.OID = C.relnamespace JOIN pg_class fc ON fc.OID = con.confrelid JOIN pg_namespace fn ON fn.OID = fc.relnamespace WHERE con.contype = 'f' AND (n.nspname, C.relname) IN (SELECT * FROM unnest($1::TEXT[], $2::TEXT[])) ORDER BY 1, 2",
+    )
+    .bind(schemas)
+    .bind(names)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows.into_iter().filter(|r| !sources.contains(r)).collect())
+}
+
+/// 枚举类型及其取值，K：类型名
+pub(crate) async fn enum_types(
+    pool: &Pool<sqlx::Postgres>,
+) -> anyhow::Result<HashMap<String, Vec<String>>> {
+    let rows = sqlx::query_as::<_, (String, String)>(
+        "SELECT t.typname::TEXT, e.enumlabel::TEXT FROM pg_type t JOIN pg_enum e ON e.enumtypid = t.OID ORDER BY t.typname, e.enumsortorder",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .fold(HashMap::new(), |mut enum_types, (type_name, label)| {
+            enum_types
+                .entry(type_name)
+                .or_insert_with(Vec::new)
+                .push(label);
+            enum_types
+        }))
+}
+
+/// 测试用的表结构样例
+#[cfg(feature = "test-support")]
+pub(crate) fn fixture() -> (Vec<super::Table>, Vec<super::Column>) {
+    let table = Table {
+        table_catalog: "fixture".to_string(),
+        table_schema: "public".to_string(),
+        table_name: "user".to_string(),
+        description: Some("用户".to_string()),
+        ..Default::default()
+    };
+    let columns = [
+        ("id", "int8", "NO", true, "主键"),
+        ("name", "varchar", "NO", false, "名称"),
+        ("email", "varchar", "YES", false, "邮箱"),
+        ("amount", "numeric", "YES", false, "余额"),
+        ("is_active", "bool", "NO", false, "是否启用"),
+        ("created_at", "timestamptz", "NO", false, "创建时间"),
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(i, (name, ty, nullable, pk, comment))| {
+        TableColumn {
+            table_catalog: "fixture".to_string(),
+            table_schema: "public".to_string(),
+            table_name: "user".to_string(),
+            column_name: name.to_string(),
+            ordinal_position: i as i32 + 1,
+            is_nullable: nullable.to_string(),
+            data_type: ty.to_string(),
+            is_primary_key: pk,
+            is_indexed: pk,
+            description: Some(comment.to_string()),
+            ..Default::default()
+        }
+        .into()
+    })
+    .collect();
+    (vec![table.into()], columns)
 }
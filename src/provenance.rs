@@ -0,0 +1,120 @@
+//! 模板上下文中的生成信息
+//!
+//! 模板可以根据驱动、数据库版本和生成选项输出不同的代码，也可以在文件头注明生成来源，如：
+//!
+//! ```text
+//! // 由 sqlx-db-cli {{ generator_version }} 根据 {{ driver }} 数据库 {{ database_name }}{% if server_version %}（{{ server_version }}）{% endif %} 生成
+//! {% if driver == "postgres" %}...{% endif %}
+//! ```
+
+use serde::Serialize;
+
+use crate::{
+    transliterate::Transliterate, BinaryEncoding, Emit, Generator, IgnoreColumns, TimestampPolicy,
+};
+
+/// 影响生成结果的选项，模板上下文中的 options
+#[derive(Serialize, Debug, Clone)]
+pub struct GenerationOptions {
+    /// 指定的表名，为空时生成所有表
+    pub table_names: Vec<String>,
+    /// 选择表的正则表达式
+    pub include_regex: Option<String>,
+    /// 不生成的表
+    pub exclude: Vec<String>,
+    /// 生成结构体名和文件名时去掉的表名前缀
+    pub strip_prefix: Vec<String>,
+    /// 中文名称的转换方式
+    pub transliterate: Option<Transliterate>,
+    /// 不生成的列
+    pub ignore_columns: Vec<IgnoreColumns>,
+    pub emit: Vec<Emit>,
+    pub timestamp_policy: Option<TimestampPolicy>,
+    pub binary_encoding: Option<BinaryEncoding>,
+    pub portable: bool,
+    pub ci_string: bool,
+    pub strict_serde: bool,
+    pub json_helpers: bool,
+    pub doc_examples: bool,
+    pub db_struct: bool,
+    /// 所有表生成到一个文件中
+    pub single_file: bool,
+    pub try_from_row: bool,
+    pub any_row: bool,
+    pub patch: bool,
+    pub schema_check: bool,
+    pub metrics: bool,
+    pub fetch_optional: bool,
+    pub qualify_schema: bool,
+    pub nested_schemas: bool,
+    /// 附加的 Sqlite 数据库，库名=文件路径
+    pub attachments: Vec<String>,
+    pub fk_closure: bool,
+    pub default_page_size: i64,
+    pub max_page_size: i64,
+    pub wide_table_columns: u64,
+    /// 从 DDL 文件生成时的文件路径
+    pub from_ddl: Option<String>,
+    /// 从保存的目录数据生成时的文件路径
+    pub offline_catalog: Option<String>,
+    /// 执行迁移后生成时的迁移目录
+    pub migrate_dir: Option<String>,
+}
+
+impl GenerationOptions {
+    pub fn new(generator: &Generator) -> Self {
+        Self {
+            table_names: generator
+                .table_names
+                .split(',')
+                .filter(|t| !t.is_empty())
+                .map(str::to_string)
+                .collect(),
+            include_regex: generator.include_regex.as_ref().map(|r| r.to_string()),
+            exclude: generator
+                .exclude
+                .split(',')
+                .filter(|t| !t.is_empty())
+                .map(str::to_string)
+                .collect(),
+            strip_prefix: generator
+                .strip_prefix
+                .split(',')
+                .filter(|p| !p.is_empty())
+                .map(str::to_string)
+                .collect(),
+            transliterate: generator.transliterate,
+            ignore_columns: generator.ignore_columns.clone(),
+            emit: generator.emit.clone(),
+            timestamp_policy: generator.timestamp_policy,
+            binary_encoding: generator.binary_encoding,
+            portable: generator.portable,
+            ci_string: generator.ci_string,
+            strict_serde: generator.strict_serde,
+            json_helpers: generator.json_helpers,
+            doc_examples: generator.doc_examples,
+            db_struct: generator.db_struct,
+            single_file: generator.single_file().is_some(),
+            try_from_row: generator.try_from_row,
+            any_row: generator.any_row,
+            patch: generator.patch,
+            schema_check: generator.schema_check,
+            metrics: generator.metrics,
+            fetch_optional: generator.fetch_optional,
+            qualify_schema: generator.qualify_schema,
+            nested_schemas: generator.nested_schemas,
+            attachments: generator
+                .attachments
+                .iter()
+                .map(|a| format!("{}={}", a.name, a.path))
+                .collect(),
+            fk_closure: generator.fk_closure,
+            default_page_size: generator.default_page_size,
+            max_page_size: generator.max_page_size,
+            wide_table_columns: generator.wide_table_columns,
+            from_ddl: generator.from_ddl.clone(),
+            offline_catalog: generator.offline_catalog.clone(),
+            migrate_dir: generator.migrate_dir.clone(),
+        }
+    }
+}
@@ -0,0 +1,146 @@
+//! --from-query：为查询结果生成结构体
+//!
+//! 报表等查询的结果往往与任何一张表都不对应。将查询包装为 `SELECT * FROM (查询) LIMIT 0`，
+//! 通过 sqlx 的 describe 读取结果列的名称、类型和是否可为空，按读取表结构时相同的规则映射 Rust 类型；
+//! 没有参数的查询还会执行一次，确认查询可以执行。
+//!
+//! 结果作为一张表参与生成，模块中只有结构体、查询语句常量 `SQL` 和 `fetch_all`，不生成增删改查。
+
+use heck::ToSnakeCase;
+use sqlx::{Column as _, Either, Executor, Pool, TypeInfo as _};
+
+use crate::{
+    error::{classify, Error},
+    mysql, postgres, sqlite,
+    version::ServerVersion,
+};
+use crate::{Column, Driver, Generator, Table};
+
+/// 没有指定 --query-name、直接传入语句时的模块名称
+const DEFAULT_NAME: &str = "query_result";
+
+/// 执行查询并读取结果列，返回对应的表和列
+pub(crate) async fn describe(
+    generator: &Generator,
+    query: &str,
+) -> anyhow::Result<(Table, Vec<Column>)> {
+    let path = std::path::Path::new(query);
+    let (sql, stem) = match path.is_file() {
+        true => (
+            std::fs::read_to_string(path)?,
+            path.file_stem().map(|s| s.to_string_lossy().to_string()),
+        ),
+        false => (query.to_string(), None),
+    };
+    let name = generator
+        .query_name
+        .clone()
+        .or(stem)
+        .unwrap_or_else(|| DEFAULT_NAME.to_string())
+        .to_snake_case();
+    let sql = sql.trim().trim_end_matches(';').trim().to_string();
+    let wrapped = format!("SELECT * FROM ({sql}) sqlx_db_cli_query LIMIT 0");
+
+    let driver = generator.driver()?;
+    let result = match driver {
+        Driver::Mysql => {
+            let version = generator.server_version().await?;
+            let pool = generator
+                .connect::<sqlx::MySql>(&version)
+                .await
+                .map_err(|e| classify(e, Error::Connect))?;
+            result_columns(&pool, &wrapped).await?
+        }
+        Driver::Postgres => {
+            let version = generator.server_version().await?;
+            let pool = generator
+                .connect::<sqlx::Postgres>(&version)
+                .await
+                .map_err(|e| classify(e, Error::Connect))?;
+            result_columns(&pool, &wrapped).await?
+        }
+        Driver::Sqlite => {
+            let pool = generator
+                .connect::<sqlx::Sqlite>(&ServerVersion::default())
+                .await
+                .map_err(|e| classify(e, Error::Connect))?;
+            result_columns(&pool, &wrapped).await?
+        }
+    };
+
+    let columns = result
+        .into_iter()
+        .enumerate()
+        .map(|(idx, (column_name, type_name, is_nullable))| {
+            // 没有别名的表达式列，如 count(*)、1+1，列名不是合法的字段名
+            let field = match column_name.to_snake_case() {
+                field if field.is_empty() => format!("column_{}", idx + 1),
+                field if field.starts_with(|c: char| c.is_ascii_digit()) => {
+                    format!("column_{field}")
+                }
+                field => field,
+            };
+            let field_type = match driver {
+                Driver::Mysql => mysql::t2t(&type_name),
+                Driver::Postgres => postgres::t2t(&type_name),
+                Driver::Sqlite => sqlite::t2t(&type_name),
+            };
+            Column {
+                table_name: Some(name.clone()),
+                name: Some(crate::column_keywords(&field)),
+                rename: (field != column_name).then(|| column_name.clone()),
+                ordinal: idx as u32 + 1,
+                is_nullable,
+                column_type: Some(type_name.to_lowercase()),
+                comment: Some(column_name.clone()),
+                field_type: field_type.to_string(),
+                multi_world: Some(column_name.contains(['_', '-'])),
+                ..Default::default()
+            }
+        })
+        .collect::<Vec<_>>();
+    if columns.is_empty() {
+        anyhow::bail!("查询结果没有列：{sql}");
+    }
+
+    let table = Table {
+        name: name.clone(),
+        comment: format!("{name} 的查询结果"),
+        query: Some(sql),
+        ..Default::default()
+    };
+    Ok((table, columns))
+}
+
+/// 结果列的名称、数据库类型名称（大写）和是否可为空，无法推断是否可为空时按可为空处理
+async fn result_columns<DB>(
+    pool: &Pool<DB>,
+    sql: &str,
+) -> anyhow::Result<Vec<(String, String, bool)>>
+where
+    DB: sqlx::Database,
+    for<'c> &'c Pool<DB>: Executor<'c, Database = DB>,
+{
+    let describe = pool.describe(sql).await?;
+    // 带参数的查询无法直接执行，只读取结果列
+    let parameters = match describe.parameters() {
+        Some(Either::Left(parameters)) => parameters.len(),
+        Some(Either::Right(count)) => count,
+        None => 0,
+    };
+    if parameters == 0 {
+        pool.execute(sql).await?;
+    }
+    Ok(describe
+        .columns()
+        .iter()
+        .enumerate()
+        .map(|(idx, column)| {
+            (
+                column.name().to_string(),
+                column.type_info().name().to_uppercase(),
+                describe.nullable(idx).unwrap_or(true),
+            )
+        })
+        .collect())
+}
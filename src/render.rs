@@ -0,0 +1,336 @@
+//! 作为库使用时渲染单张表
+//!
+//! 已有表结构（如由设计工具产生）时，用 [`RenderOptions`] 直接渲染单张表的代码，不连接数据库也不写入文件。
+//! 选项由 [`RenderOptions::new`] 创建后用 `with_*` 方法设置，不需要拼接命令行参数；没有设置的选项取命令行的默认值。
+
+use clap::Parser;
+
+use crate::{
+    transliterate::Transliterate, BinaryEncoding, Column, Driver, Emit, Generator, Table,
+    TimestampPolicy,
+};
+
+/// 渲染选项，字段与同名的命令行参数含义相同
+///
+/// 之后可能增加新的选项，不能用结构体字面量创建，需要用 [`RenderOptions::new`] 和 `with_*` 方法
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct RenderOptions {
+    /// 数据库驱动
+    pub driver: Driver,
+    /// 数据库名称，MySQL 中表所在的库与之相同时 SQL 中的表名不带库名
+    pub database: String,
+    /// 表名、列名转为标识符的方式
+    pub transliterate: Option<Transliterate>,
+    /// 生成结构体名和文件名时去掉的表名前缀
+    pub strip_prefix: Vec<String>,
+    /// 只使用三种驱动都支持的类型
+    pub portable: bool,
+    /// 时间列的映射方式
+    pub timestamp_policy: Option<TimestampPolicy>,
+    /// 二进制列在 JSON 中的编码方式
+    pub binary_encoding: Option<BinaryEncoding>,
+    /// 不区分大小写的字符串列使用 CiString
+    pub ci_string: bool,
+    /// 严格反序列化
+    pub strict_serde: bool,
+    /// 生成 from_json_value/to_json_value
+    pub json_helpers: bool,
+    /// 在字段文档中加上示例值
+    pub doc_examples: bool,
+    /// 生成逐列解码的 TryFrom<&Row> 实现
+    pub try_from_row: bool,
+    /// 生成 TryFrom<&sqlx::any::AnyRow>
+    pub any_row: bool,
+    /// id 主键使用 newtype 包装
+    pub id_newtype: bool,
+    /// 生成持有连接池的 Db
+    pub db_struct: bool,
+    /// fetch_by_id 返回 Option
+    pub fetch_optional: bool,
+    /// 生成部分更新的 {结构体}Patch
+    pub patch: bool,
+    /// 生成表结构指纹和 verify_schema
+    pub schema_check: bool,
+    /// 生成 metrics 模块，上报增删改查的耗时
+    pub metrics: bool,
+    /// 额外生成的内容
+    pub emit: Vec<Emit>,
+    /// 必须带索引条件才能查询列表的表
+    pub must_filter: Vec<String>,
+    /// 分页查询默认每页的条数
+    pub default_page_size: i64,
+    /// 分页查询每页的最大条数
+    pub max_page_size: i64,
+    /// 超过该列数的表按宽表生成
+    pub wide_table_columns: u64,
+}
+
+impl RenderOptions {
+    /// 与命令行默认值相同的选项
+    pub fn new(driver: Driver) -> Self {
+        Self {
+            driver,
+            database: String::new(),
+            transliterate: None,
+            strip_prefix: vec![],
+            portable: false,
+            timestamp_policy: None,
+            binary_encoding: None,
+            ci_string: false,
+            strict_serde: false,
+            json_helpers: false,
+            doc_examples: false,
+            try_from_row: false,
+            any_row: false,
+            id_newtype: false,
+            db_struct: false,
+            fetch_optional: false,
+            patch: false,
+            schema_check: false,
+            metrics: false,
+            emit: vec![],
+            must_filter: vec![],
+            default_page_size: 20,
+            max_page_size: 1000,
+            wide_table_columns: 1000,
+        }
+    }
+
+    /// 数据库名称
+    pub fn with_database(mut self, database: &str) -> Self {
+        self.database = database.to_string();
+        self
+    }
+
+    /// 表名、列名转为标识符的方式
+    pub fn with_transliterate(mut self, transliterate: Transliterate) -> Self {
+        self.transliterate = Some(transliterate);
+        self
+    }
+
+    /// 去掉的表名前缀
+    pub fn with_strip_prefix(mut self, strip_prefix: &[&str]) -> Self {
+        self.strip_prefix = strip_prefix.iter().map(|p| p.to_string()).collect();
+        self
+    }
+
+    /// 只使用三种驱动都支持的类型
+    pub fn with_portable(mut self, portable: bool) -> Self {
+        self.portable = portable;
+        self
+    }
+
+    /// 时间列的映射方式
+    pub fn with_timestamp_policy(mut self, timestamp_policy: TimestampPolicy) -> Self {
+        self.timestamp_policy = Some(timestamp_policy);
+        self
+    }
+
+    /// 二进制列在 JSON 中的编码方式
+    pub fn with_binary_encoding(mut self, binary_encoding: BinaryEncoding) -> Self {
+        self.binary_encoding = Some(binary_encoding);
+        self
+    }
+
+    /// 不区分大小写的字符串列使用 CiString
+    pub fn with_ci_string(mut self, ci_string: bool) -> Self {
+        self.ci_string = ci_string;
+        self
+    }
+
+    /// 严格反序列化
+    pub fn with_strict_serde(mut self, strict_serde: bool) -> Self {
+        self.strict_serde = strict_serde;
+        self
+    }
+
+    /// 生成 from_json_value/to_json_value
+    pub fn with_json_helpers(mut self, json_helpers: bool) -> Self {
+        self.json_helpers = json_helpers;
+        self
+    }
+
+    /// 在字段文档中加上示例值
+    pub fn with_doc_examples(mut self, doc_examples: bool) -> Self {
+        self.doc_examples = doc_examples;
+        self
+    }
+
+    /// 生成逐列解码的 TryFrom<&Row> 实现
+    pub fn with_try_from_row(mut self, try_from_row: bool) -> Self {
+        self.try_from_row = try_from_row;
+        self
+    }
+
+    /// 生成 TryFrom<&sqlx::any::AnyRow>
+    pub fn with_any_row(mut self, any_row: bool) -> Self {
+        self.any_row = any_row;
+        self
+    }
+
+    /// id 主键使用 newtype 包装
+    pub fn with_id_newtype(mut self, id_newtype: bool) -> Self {
+        self.id_newtype = id_newtype;
+        self
+    }
+
+    /// 生成持有连接池的 Db
+    pub fn with_db_struct(mut self, db_struct: bool) -> Self {
+        self.db_struct = db_struct;
+        self
+    }
+
+    /// fetch_by_id 返回 Option
+    pub fn with_fetch_optional(mut self, fetch_optional: bool) -> Self {
+        self.fetch_optional = fetch_optional;
+        self
+    }
+
+    /// 生成部分更新的 {结构体}Patch
+    pub fn with_patch(mut self, patch: bool) -> Self {
+        self.patch = patch;
+        self
+    }
+
+    /// 生成表结构指纹和 verify_schema
+    pub fn with_schema_check(mut self, schema_check: bool) -> Self {
+        self.schema_check = schema_check;
+        self
+    }
+
+    /// 生成 metrics 模块
+    pub fn with_metrics(mut self, metrics: bool) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// 额外生成的内容
+    pub fn with_emit(mut self, emit: &[Emit]) -> Self {
+        self.emit = emit.to_vec();
+        self
+    }
+
+    /// 必须带索引条件才能查询列表的表
+    pub fn with_must_filter(mut self, must_filter: &[&str]) -> Self {
+        self.must_filter = must_filter.iter().map(|t| t.to_string()).collect();
+        self
+    }
+
+    /// 分页查询默认每页的条数
+    pub fn with_default_page_size(mut self, default_page_size: i64) -> Self {
+        self.default_page_size = default_page_size;
+        self
+    }
+
+    /// 分页查询每页的最大条数
+    pub fn with_max_page_size(mut self, max_page_size: i64) -> Self {
+        self.max_page_size = max_page_size;
+        self
+    }
+
+    /// 超过该列数的表按宽表生成
+    pub fn with_wide_table_columns(mut self, wide_table_columns: u64) -> Self {
+        self.wide_table_columns = wide_table_columns;
+        self
+    }
+
+    /// 按驱动和类型选项确定列的 Rust 类型，返回有损映射等警告
+    pub fn map_types(&self, columns: &mut [Column]) -> anyhow::Result<Vec<String>> {
+        self.generator()?.map_types(columns)
+    }
+
+    /// 渲染单张表的代码，与写入文件时的内容一致
+    ///
+    /// columns 为该表的列，需要先经过 [`RenderOptions::map_types`] 处理
+    pub fn render_table(&self, table: &Table, columns: &[Column]) -> anyhow::Result<String> {
+        self.generator()?.render_table(table, columns)
+    }
+
+    fn generator(&self) -> anyhow::Result<Generator> {
+        let mut generator = Generator::try_parse_from(["sqlx-db-cli"])?;
+        generator.driver = Some(self.driver);
+        generator.database = self.database.clone();
+        generator.transliterate = self.transliterate;
+        generator.strip_prefix = self.strip_prefix.join(",");
+        generator.portable = self.portable;
+        generator.timestamp_policy = self.timestamp_policy;
+        generator.binary_encoding = self.binary_encoding;
+        generator.ci_string = self.ci_string;
+        generator.strict_serde = self.strict_serde;
+        generator.json_helpers = self.json_helpers;
+        generator.doc_examples = self.doc_examples;
+        generator.try_from_row = self.try_from_row;
+        generator.any_row = self.any_row;
+        generator.id_newtype = self.id_newtype;
+        generator.db_struct = self.db_struct;
+        generator.fetch_optional = self.fetch_optional;
+        generator.patch = self.patch;
+        generator.schema_check = self.schema_check;
+        generator.metrics = self.metrics;
+        generator.emit = self.emit.clone();
+        generator.must_filter = self.must_filter.join(",");
+        generator.default_page_size = self.default_page_size;
+        generator.max_page_size = self.max_page_size;
+        generator.wide_table_columns = self.wide_table_columns;
+        Ok(generator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_match_command_line() {
+        let parsed = Generator::try_parse_from(["sqlx-db-cli"]).unwrap();
+        let options = RenderOptions::new(Driver::Sqlite);
+        assert_eq!(options.default_page_size, parsed.default_page_size);
+        assert_eq!(options.max_page_size, parsed.max_page_size);
+        assert_eq!(options.wide_table_columns, parsed.wide_table_columns);
+        assert_eq!(options.strip_prefix.join(","), parsed.strip_prefix);
+        assert_eq!(options.must_filter.join(","), parsed.must_filter);
+    }
+
+    #[test]
+    fn renders_table_with_options() {
+        let table = Table {
+            schema: "shop".to_string(),
+            name: "user_info".to_string(),
+            comment: "用户".to_string(),
+            ..Default::default()
+        };
+        let column = |ordinal, name: &str, column_type: &str, field_type: &str| Column {
+            schema: Some("shop".to_string()),
+            table_name: Some("user_info".to_string()),
+            name: Some(name.to_string()),
+            ordinal,
+            is_primary_key: ordinal == 1,
+            is_nullable: ordinal != 1,
+            column_type: Some(column_type.to_string()),
+            field_type: field_type.to_string(),
+            ..Default::default()
+        };
+        let mut columns = vec![
+            column(1, "id", "bigint", "i64"),
+            column(2, "nick_name", "varchar(32)", "String"),
+        ];
+
+        let options = RenderOptions::new(Driver::Mysql)
+            .with_database("shop")
+            .with_strict_serde(true)
+            .with_strip_prefix(&["user_"]);
+        options.map_types(&mut columns).unwrap();
+
+        let contents = options.render_table(&table, &columns).unwrap();
+        assert!(contents.contains("pub struct Info {"), "{contents}");
+        assert!(contents.contains("deny_unknown_fields"), "{contents}");
+        assert!(contents.contains("pub id: i64,"), "{contents}");
+        assert!(
+            contents.contains(
+                "#[serde(deserialize_with = \"Option::deserialize\")]\n    pub nick_name: Option<String>,"
+            ),
+            "{contents}"
+        );
+    }
+}
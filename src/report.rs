@@ -0,0 +1,49 @@
+//! `--output-format json` 的运行报告
+//!
+//! 生成结束时在标准输出中只输出一个 JSON 对象，其他工具可以直接解析；日志仍然输出到标准错误。
+//! 出错时同样输出 JSON，`status` 为 `error`。
+
+use serde::Serialize;
+
+use crate::{Changes, Error};
+
+/// 运行报告
+#[derive(Serialize, Debug)]
+struct Report<'a> {
+    /// changed、unchanged、error
+    status: &'a str,
+    dry_run: bool,
+    #[serde(flatten)]
+    changes: Option<&'a Changes>,
+    error: Option<String>,
+    exit_code: u8,
+}
+
+/// 输出生成结果
+pub fn print(changes: &Changes, dry_run: bool) -> anyhow::Result<()> {
+    let changed = !changes.is_empty();
+    let report = Report {
+        status: if changed { "changed" } else { "unchanged" },
+        dry_run,
+        changes: Some(changes),
+        error: None,
+        // 与 main 中的退出码一致：dry-run 时有变化为 1
+        exit_code: u8::from(dry_run && changed),
+    };
+    println!("{:#}", tera::to_value(&report)?);
+    Ok(())
+}
+
+/// 输出错误，退出码见 [`Error::exit_code`]
+pub fn print_error(e: &anyhow::Error, dry_run: bool) {
+    let report = Report {
+        status: "error",
+        dry_run,
+        changes: None,
+        error: Some(format!("{e:#}")),
+        exit_code: Error::exit_code_of(e),
+    };
+    if let Ok(value) = tera::to_value(&report) {
+        println!("{value:#}");
+    }
+}
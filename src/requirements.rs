@@ -0,0 +1,214 @@
+//! 生成代码需要的依赖
+//!
+//! 根据实际生成的字段类型计算 sqlx 的 feature（time、bigdecimal、uuid、json 等）和需要引入的 crate，
+//! 生成结束时输出可以直接复制到 Cargo.toml 的依赖，指定 `--requirements` 时同时写入输出目录的 REQUIREMENTS.toml。
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{Column, Driver, Emit, FeatureGroup};
+
+/// 生成的代码始终需要的依赖
+const BASE: [(&str, &str); 8] = [
+    ("async_static", r#""0.1""#),
+    ("log", r#""0.4""#),
+    ("once_cell", r#""1""#),
+    ("serde", r#"{ version = "1", features = ["derive"] }"#),
+    ("serde_json", r#""1""#),
+    ("thiserror", r#""1""#),
+    // sqlx 使用 tokio 运行时
+    ("tokio", r#"{ version = "1", features = ["full"] }"#),
+    (
+        "validator",
+        r#"{ version = "0.16", features = ["derive"] }"#,
+    ),
+];
+
+/// 依赖及 sqlx 的 feature
+#[derive(Debug, Clone)]
+pub struct Requirements {
+    driver: Driver,
+    /// sqlx 的 feature，不含运行时和驱动
+    features: BTreeSet<&'static str>,
+    /// K：crate 名称，V：版本或依赖表
+    crates: BTreeMap<&'static str, &'static str>,
+    /// 是否使用了 sqlx_postgres 中的类型
+    sqlx_postgres: bool,
+    /// 是否生成了由 any feature 控制的 AnyRow 解码
+    any_row: bool,
+    /// --feature-group 分组的 feature，生成的模块由这些 feature 控制是否编译
+    feature_groups: BTreeSet<String>,
+}
+
+impl Requirements {
+    fn new(driver: Driver) -> Self {
+        Self {
+            driver,
+            features: BTreeSet::new(),
+            crates: BTreeMap::from_iter(BASE),
+            sqlx_postgres: false,
+            any_row: false,
+            feature_groups: BTreeSet::new(),
+        }
+    }
+
+    /// 所有类型都可能用到的依赖，用于编译检查的临时 crate
+    pub fn all(driver: Driver) -> Self {
+        let mut requirements = Self::new(driver);
+        for field_type in [
+            "time::Date",
+            "bigdecimal::BigDecimal",
+            "uuid::Uuid",
+            "serde_json::Value",
+        ] {
+            requirements.add(field_type);
+        }
+        requirements.add_uuid_v4();
+        requirements.sqlx_postgres = matches!(driver, Driver::Postgres);
+        requirements
+    }
+
+    /// 根据生成的字段类型计算依赖
+    pub fn collect<'a>(driver: Driver, columns: impl IntoIterator<Item = &'a Column>) -> Self {
+        let mut requirements = Self::new(driver);
+        let mut uuid_v4 = false;
+        for column in columns {
+            requirements.add(&column.field_type);
+            uuid_v4 |= column.needs_client_uuid();
+        }
+        if uuid_v4 {
+            requirements.add_uuid_v4();
+        }
+        requirements
+    }
+
+    /// 额外生成的代码需要的依赖
+    pub fn with_emit(mut self, emit: &[Emit]) -> Self {
+        if emit.contains(&Emit::Axum) {
+            self.crates.insert("axum", r#""0.7""#);
+        }
+        self
+    }
+
+    /// 生成 Db 时不需要 async_static
+    pub fn with_db_struct(mut self, db_struct: bool) -> Self {
+        if db_struct {
+            self.crates.remove("async_static");
+        }
+        self
+    }
+
+    /// AnyRow 解码时从字符串解析时间需要 time 的 parsing、macros feature
+    pub fn with_any_row(mut self, any_row: bool) -> Self {
+        self.any_row = any_row;
+        if any_row && self.crates.contains_key("time") {
+            self.crates.insert(
+                "time",
+                r#"{ version = "0.3", features = ["serde", "parsing", "macros"] }"#,
+            );
+        }
+        self
+    }
+
+    /// 按 --feature-group 分组的模块使用的 feature
+    pub fn with_feature_groups(mut self, groups: &[FeatureGroup]) -> Self {
+        self.feature_groups
+            .extend(groups.iter().map(|g| g.feature.clone()));
+        self
+    }
+
+    /// 插入前在客户端生成 UUID 需要 uuid 的 v4 feature
+    fn add_uuid_v4(&mut self) {
+        self.crates
+            .insert("uuid", r#"{ version = "1", features = ["serde", "v4"] }"#);
+    }
+
+    fn add(&mut self, field_type: &str) {
+        let (feature, dependency) = match field_type {
+            t if t.starts_with("time::") || t == "UtcDateTime" => (
+                "time",
+                Some(("time", r#"{ version = "0.3", features = ["serde"] }"#)),
+            ),
+            "bigdecimal::BigDecimal" => (
+                "bigdecimal",
+                Some(("bigdecimal", r#"{ version = "0.3", features = ["serde"] }"#)),
+            ),
+            "uuid::Uuid" => (
+                "uuid",
+                Some(("uuid", r#"{ version = "1", features = ["serde"] }"#)),
+            ),
+            "serde_json::Value" => ("json", None),
+            "std::net::IpAddr" => ("ipnetwork", None),
+            "mac_address::MacAddress" => (
+                "mac_address",
+                Some(("mac_address", r#"{ version = "1", features = ["serde"] }"#)),
+            ),
+            "bit_vec::BitVec" => (
+                "bit-vec",
+                Some(("bit-vec", r#"{ version = "0.6", features = ["serde"] }"#)),
+            ),
+            // PgTimeTz 等类型在 sqlx_postgres 中，区间和时间类型需要 time
+            t if t.starts_with("sqlx_postgres::") => {
+                self.sqlx_postgres = true;
+                (
+                    "time",
+                    Some(("time", r#"{ version = "0.3", features = ["serde"] }"#)),
+                )
+            }
+            _ => return,
+        };
+        self.features.insert(feature);
+        if let Some((name, version)) = dependency {
+            self.crates.insert(name, version);
+        }
+    }
+
+    /// Cargo.toml 中 [features] 下生成的代码使用的 feature，没有时为空
+    pub fn features(&self) -> String {
+        let mut features = String::new();
+        if self.any_row {
+            features.push_str("any = [\"sqlx/any\"]\n");
+        }
+        for group in self.feature_groups.iter().filter(|g| *g != "any") {
+            features.push_str(&format!("{group} = []\n"));
+        }
+        features
+    }
+
+    /// Cargo.toml 中 [dependencies] 下的依赖，按名称排序
+    pub fn dependencies(&self) -> String {
+        let driver = match self.driver {
+            Driver::Mysql => "mysql",
+            Driver::Postgres => "postgres",
+            Driver::Sqlite => "sqlite",
+        };
+        let features = self
+            .features
+            .iter()
+            .map(|f| format!(r#", "{f}""#))
+            .collect::<String>();
+        let mut crates = self
+            .crates
+            .iter()
+            .map(|(name, version)| (name.to_string(), version.to_string()))
+            .collect::<BTreeMap<_, _>>();
+        crates.insert(
+            "sqlx".to_string(),
+            format!(
+                r#"{{ version = "0.7", features = ["runtime-tokio-native-tls", "{driver}"{features}] }}"#
+            ),
+        );
+        if self.sqlx_postgres {
+            crates.insert(
+                "sqlx-postgres".to_string(),
+                format!(
+                    r#"{{ version = "0.7", features = [{}] }}"#,
+                    features.trim_start_matches(", ")
+                ),
+            );
+        }
+        crates
+            .iter()
+            .map(|(name, version)| format!("{name} = {version}\n"))
+            .collect()
+    }
+}
@@ -0,0 +1,276 @@
+//! 数据库结构模型
+//!
+//! 三种驱动读取到的表结构都会统一转换为这里的类型，
+//! 可以直接基于这些类型编写自己的代码生成逻辑。
+
+use std::fs;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{attributes::FieldAttributes, sql};
+
+/// 表信息
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct Table {
+    /// 所属库或模式
+    pub schema: String,
+    /// 表名
+    pub name: String,
+    /// 表注释
+    pub comment: String,
+    /// 是否为分区表的子分区（PostgreSQL）
+    pub is_partition: bool,
+    /// 子分区所属的父表（PostgreSQL）
+    pub partition_of: Option<String>,
+    /// 分区键定义，仅分区父表有值，如：RANGE (created_at)（PostgreSQL）
+    pub partition_key: Option<String>,
+    /// 继承的父表，不含分区关系（PostgreSQL）
+    pub inherits: Vec<String>,
+    /// 统计信息，指定 --table-stats 时读取
+    pub stats: Option<TableStats>,
+    /// 所属库或模式的注释（PostgreSQL 的 COMMENT ON DATABASE/SCHEMA、MariaDB 的库注释），
+    /// 生成为 mod.rs 的模块文档
+    pub schema_comment: Option<String>,
+    /// 唯一约束和排除约束（PostgreSQL），不含主键
+    pub constraints: Vec<Constraint>,
+    /// 弃用说明，表注释含有 [deprecated: 说明] 或由 --deprecated 指定时有值，没有说明时为空字符串
+    pub deprecated: Option<String>,
+    /// 查询语句，--from-query 时表示查询结果，只生成结构体和执行查询的函数
+    pub query: Option<String>,
+}
+
+/// 表级约束
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct Constraint {
+    /// 约束名，可用于 ON CONFLICT ON CONSTRAINT
+    pub name: String,
+    /// unique、exclusion
+    pub kind: String,
+    /// 约束涉及的列，按定义中的顺序
+    pub columns: Vec<String>,
+    /// 约束定义，如：EXCLUDE USING gist (room WITH =, during WITH &&)
+    pub definition: String,
+    /// 是否可延迟（DEFERRABLE）
+    pub deferrable: bool,
+    /// 是否默认延迟到事务提交时检查（INITIALLY DEFERRED）
+    pub initially_deferred: bool,
+}
+
+/// 表的统计信息
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct TableStats {
+    /// 估算的行数
+    pub rows: Option<i64>,
+    /// 数据占用的字节数
+    pub data_length: Option<i64>,
+    /// 下一个自增值（MySQL）
+    pub auto_increment: Option<i64>,
+}
+
+/// 表列信息
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct Column {
+    /// 所属库或模式
+    pub schema: Option<String>,
+    /// 所属表名
+    pub table_name: Option<String>,
+    /// 列名，为 Rust 关键字时带有 r# 前缀
+    pub name: Option<String>,
+    /// 在表定义中的位置，从 1 开始，生成的字段按该顺序排列
+    pub ordinal: u32,
+    /// 默认值
+    pub default: Option<String>,
+    /// 附加信息（MySQL 的 EXTRA），如：auto_increment、on update CURRENT_TIMESTAMP
+    pub extra: Option<String>,
+    /// 生成列的表达式（MySQL 的 GENERATION_EXPRESSION），值由数据库计算
+    pub generation_expression: Option<String>,
+    /// 最大长度
+    pub max_length: Option<i64>,
+    /// 是否允许为null
+    pub is_nullable: bool,
+    /// 是否为主键
+    pub is_primary_key: bool,
+    /// 是否为某个索引的第一列，可以作为走索引的查询条件
+    pub is_indexed: bool,
+    /// 数据库中的类型
+    pub column_type: Option<String>,
+    /// 列注释
+    pub comment: Option<String>,
+    /// 字符集（MySQL）
+    pub charset: Option<String>,
+    /// 排序规则，使用数据库默认排序规则时为空（PostgreSQL、Sqlite）
+    pub collation: Option<String>,
+    /// 枚举的取值，非枚举列为空
+    pub enum_values: Vec<String>,
+    /// 兼容的旧 JSON 字段名，由 --serde-alias 指定
+    pub serde_aliases: Vec<String>,
+    /// 字段名称由命名字典指定时，对应的列名
+    pub rename: Option<String>,
+    /// 由 --attributes 指定的字段属性
+    pub attributes: FieldAttributes,
+    /// 可能丢失精度的类型映射的说明，生成为字段文档中的 WARNING
+    pub lossy: Option<String>,
+    /// 字段文档中的示例值，由 --doc-examples 生成
+    pub example: Option<String>,
+    /// --portable 改变了类型时，查询时转换为的 SQL 类型，如：BIGINT
+    pub cast: Option<String>,
+
+    // 对应 Rust 类型
+    pub field_type: String,
+    pub multi_world: Option<bool>,
+}
+
+impl Column {
+    /// 是否不区分大小写：MySQL 的 *_ci、Sqlite 的 NOCASE、ICU 的 ks-level1/ks-level2 排序规则，
+    /// 以及 PostgreSQL 的 citext 类型
+    pub fn is_case_insensitive(&self) -> bool {
+        if self
+            .column_type
+            .as_deref()
+            .is_some_and(|t| t.eq_ignore_ascii_case("citext"))
+        {
+            return true;
+        }
+        self.collation.as_deref().is_some_and(|c| {
+            let c = c.to_lowercase();
+            c.ends_with("_ci")
+                || c.contains("_ci_")
+                || c == "nocase"
+                || c.contains("ks-level1")
+                || c.contains("ks-level2")
+        })
+    }
+
+    /// 是否在更新行时由数据库自动赋值（MySQL 的 ON UPDATE CURRENT_TIMESTAMP），
+    /// 生成的 UPDATE 语句不会修改该列
+    pub fn is_auto_updated(&self) -> bool {
+        self.extra
+            .as_deref()
+            .is_some_and(|e| e.to_lowercase().contains("on update"))
+    }
+
+    /// 是否为生成列，生成的 INSERT、UPDATE 语句不会写入该列
+    pub fn is_generated(&self) -> bool {
+        self.generation_expression.is_some()
+    }
+
+    /// 是否为默认值由数据库生成的 UUID 主键（gen_random_uuid()、uuid_generate_v4() 等），
+    /// 生成的 INSERT 语句不写入该列，由 RETURNING 返回
+    pub fn has_uuid_default(&self) -> bool {
+        self.is_primary_key
+            && self.field_type == "uuid::Uuid"
+            && self.default.as_deref().is_some_and(|d| {
+                let d = d.to_lowercase();
+                d.contains("gen_random_uuid(") || d.contains("uuid_generate_v")
+            })
+    }
+
+    /// 是否为没有默认值的 UUID 主键，插入前为空（nil）时在客户端生成 Uuid::new_v4()
+    pub fn needs_client_uuid(&self) -> bool {
+        self.is_primary_key && self.field_type == "uuid::Uuid" && self.default.is_none()
+    }
+
+    /// 对应的 Rust 类型是否实现了 Copy，生成代码中绑定参数时不需要借用
+    pub fn is_copy(&self) -> bool {
+        !self.enum_values.is_empty()
+            || self.field_type.starts_with("time::")
+            || self.field_type == "UtcDateTime"
+            || matches!(
+                self.field_type.as_str(),
+                "bool"
+                    | "i8"
+                    | "i16"
+                    | "i32"
+                    | "i64"
+                    | "u8"
+                    | "u16"
+                    | "u32"
+                    | "u64"
+                    | "f32"
+                    | "f64"
+                    | "uuid::Uuid"
+            )
+    }
+}
+
+/// 读取到的完整数据库结构
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct SchemaModel {
+    pub tables: Vec<Table>,
+    pub columns: Vec<Column>,
+}
+
+impl SchemaModel {
+    pub fn new(tables: Vec<Table>, columns: Vec<Column>) -> Self {
+        Self { tables, columns }
+    }
+
+    /// 读取 --save-snapshot 保存的表结构快照
+    pub fn load(file: &str) -> anyhow::Result<Self> {
+        let value = fs::read_to_string(file)
+            .map_err(|e| anyhow::anyhow!("读取 {file} 失败：{e}"))?
+            .parse::<tera::Value>()
+            .map_err(|e| anyhow::anyhow!("{file} 不是有效的 JSON：{e}"))?;
+        tera::from_value(value).map_err(|e| anyhow::anyhow!("{file} 不是表结构快照：{e}"))
+    }
+
+    /// 将表结构保存为 JSON 快照
+    pub fn save(&self, file: &str) -> anyhow::Result<()> {
+        fs::write(file, format!("{:#}\n", tera::to_value(self)?))
+            .map_err(|e| anyhow::anyhow!("写入 {file} 失败：{e}"))
+    }
+
+    /// 根据表名查找表
+    pub fn find_table(&self, name: &str) -> Option<&Table> {
+        self.tables.iter().find(|t| t.name == name)
+    }
+
+    /// 指定表的所有列，按列在表定义中的位置排列
+    pub fn columns_of(&self, table_name: &str) -> Vec<&Column> {
+        let mut columns = self
+            .columns
+            .iter()
+            .filter(|c| c.table_name.as_deref() == Some(table_name))
+            .collect::<Vec<_>>();
+        columns.sort_by_key(|c| c.ordinal);
+        columns
+    }
+
+    /// 表结构的指纹（FNV-1a 64 位），由表名、列名、数据库类型、是否可空和是否为主键计算，
+    /// 与读取的先后顺序无关
+    pub fn fingerprint(&self) -> String {
+        let mut tables = self
+            .tables
+            .iter()
+            .map(|t| t.name.as_str())
+            .collect::<Vec<_>>();
+        tables.sort();
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for table in tables {
+            let columns = self.columns_of(table).into_iter().map(|c| {
+                format!(
+                    "{}:{}:{}:{}",
+                    sql::raw_name(c),
+                    c.column_type.as_deref().unwrap_or_default(),
+                    c.is_nullable,
+                    c.is_primary_key
+                )
+            });
+            for part in std::iter::once(table.to_string()).chain(columns) {
+                for byte in part.bytes().chain([b'\n']) {
+                    hash ^= byte as u64;
+                    hash = hash.wrapping_mul(0x100000001b3);
+                }
+            }
+        }
+        format!("{hash:016x}")
+    }
+
+    /// 指定表的主键列，联合主键时返回多列
+    pub fn primary_key_of(&self, table_name: &str) -> Vec<&Column> {
+        self.columns_of(table_name)
+            .into_iter()
+            .filter(|c| c.is_primary_key)
+            .collect()
+    }
+}
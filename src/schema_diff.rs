@@ -0,0 +1,278 @@
+//! sqlx-db-cli diff：比较数据库中的表结构与已生成的结构体
+//!
+//! 从输出目录中已生成的代码解析每个结构体的字段（`TABLE_NAME` 所在的结构体，列名取 `#[sqlx(rename)]` 或字段名），
+//! 与读取到的表结构按列比较，输出每张表新增、删除和类型变化的列，用于决定是否需要重新生成。
+//! 类型按与生成时相同的选项映射，比较的是 Rust 类型，只有注释、默认值的变化不会列出。
+
+use std::{collections::BTreeMap, fs, path::Path};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Serialize;
+
+use crate::manifest::{Manifest, TypeChange};
+
+lazy_static! {
+    /// 模型的 impl 块中的表名
+    static ref TABLE_NAME: Regex =
+        Regex::new(r#"impl (\w+) \{\s*pub const TABLE_NAME: &str = "([^"]*)";"#).unwrap();
+    static ref RENAME: Regex = Regex::new(r#"#\[sqlx\(rename = "([^"]*)"\)\]"#).unwrap();
+    static ref FIELD: Regex = Regex::new(r"^\s*pub (?:r#)?(\w+): (.+),\s*$").unwrap();
+}
+
+/// 一张表的差异
+#[derive(Serialize, Debug, Clone)]
+pub struct TableDiff {
+    pub table: String,
+    /// added：数据库中有，还没有生成；removed：已生成，数据库中没有；changed：列有变化
+    pub status: &'static str,
+    /// 数据库中新增的列，格式：列名: 类型
+    pub added: Vec<String>,
+    /// 数据库中已删除的列，格式：列名: 类型
+    pub removed: Vec<String>,
+    /// 类型有变化的列
+    pub retyped: Vec<TypeChange>,
+}
+
+/// 解析文件中所有模型结构体的字段，K：表名，V：列名 → 字段类型
+pub fn parse(contents: &str) -> Manifest {
+    let mut tables = BTreeMap::new();
+    for caps in TABLE_NAME.captures_iter(contents) {
+        let Some(start) = contents.find(&format!("pub struct {} {{", &caps[1])) else {
+            continue;
+        };
+        let mut columns = BTreeMap::new();
+        let mut rename = None;
+        for line in contents[start..].lines().skip(1) {
+            if line.starts_with('}') {
+                break;
+            }
+            if let Some(r) = RENAME.captures(line) {
+                rename = Some(r[1].to_string());
+            } else if let Some(f) = FIELD.captures(line) {
+                let column = rename.take().unwrap_or_else(|| f[1].to_string());
+                columns.insert(column, f[2].trim().to_string());
+            }
+        }
+        tables.insert(caps[2].to_string(), columns);
+    }
+    Manifest(tables)
+}
+
+/// 读取目录中（不含子目录）所有 .rs 文件中的模型，单个文件时只读取该文件
+pub fn read(path: impl AsRef<Path>) -> Manifest {
+    let path = path.as_ref();
+    let files = match path.is_file() {
+        true => vec![path.to_path_buf()],
+        false => fs::read_dir(path)
+            .map(|entries| {
+                entries
+                    .filter_map(|e| e.ok().map(|e| e.path()))
+                    .filter(|p| p.extension().is_some_and(|e| e == "rs"))
+                    .collect()
+            })
+            .unwrap_or_default(),
+    };
+    let mut manifest = Manifest::default();
+    for file in files {
+        if let Ok(contents) = fs::read_to_string(&file) {
+            manifest.0.extend(parse(&contents).0);
+        }
+    }
+    manifest
+}
+
+/// 比较已生成的代码与数据库，partial 时（只选择了部分表）不列出只在代码中的表
+pub fn compare(code: &Manifest, database: &Manifest, partial: bool) -> Vec<TableDiff> {
+    let mut diffs = vec![];
+    for (table, columns) in database.0.iter() {
+        let Some(generated) = code.0.get(table) else {
+            diffs.push(TableDiff {
+                table: table.clone(),
+                status: "added",
+                added: columns.iter().map(|(c, t)| format!("{c}: {t}")).collect(),
+                removed: vec![],
+                retyped: vec![],
+            });
+            continue;
+        };
+        let diff = TableDiff {
+            table: table.clone(),
+            status: "changed",
+            added: columns
+                .iter()
+                .filter(|(c, _)| !generated.contains_key(*c))
+                .map(|(c, t)| format!("{c}: {t}"))
+                .collect(),
+            removed: generated
+                .iter()
+                .filter(|(c, _)| !columns.contains_key(*c))
+                .map(|(c, t)| format!("{c}: {t}"))
+                .collect(),
+            retyped: code.type_changes(&Manifest(BTreeMap::from([(
+                table.clone(),
+                columns.clone(),
+            )]))),
+        };
+        if !diff.added.is_empty() || !diff.removed.is_empty() || !diff.retyped.is_empty() {
+            diffs.push(diff);
+        }
+    }
+    if !partial {
+        for (table, columns) in code.0.iter() {
+            if !database.0.contains_key(table) {
+                diffs.push(TableDiff {
+                    table: table.clone(),
+                    status: "removed",
+                    added: vec![],
+                    removed: columns.iter().map(|(c, t)| format!("{c}: {t}")).collect(),
+                    retyped: vec![],
+                });
+            }
+        }
+    }
+    diffs
+}
+
+/// 按表输出差异，最后一行为汇总
+pub fn print(diffs: &[TableDiff]) {
+    for diff in diffs {
+        match diff.status {
+            "added" => println!("{}: not generated", diff.table),
+            "removed" => println!("{}: not in database", diff.table),
+            _ => println!("{}:", diff.table),
+        }
+        if diff.status != "changed" {
+            continue;
+        }
+        for column in diff.added.iter() {
+            println!("  + {column}");
+        }
+        for column in diff.removed.iter() {
+            println!("  - {column}");
+        }
+        for change in diff.retyped.iter() {
+            println!("  ~ {}: {} -> {}", change.column, change.old, change.new);
+        }
+    }
+    // 新增、删除的表只按表计数
+    let count = |f: fn(&TableDiff) -> usize| {
+        diffs
+            .iter()
+            .filter(|d| d.status == "changed")
+            .map(f)
+            .sum::<usize>()
+    };
+    println!(
+        "diff: status={} tables={} added={} removed={} retyped={}",
+        if diffs.is_empty() {
+            "unchanged"
+        } else {
+            "changed"
+        },
+        diffs.len(),
+        count(|d| d.added.len()),
+        count(|d| d.removed.len()),
+        count(|d| d.retyped.len()),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODE: &str = r#"
+pub struct UserInfo {
+    pub id: i64,
+    #[sqlx(rename = "type")]
+    pub r#type: String,
+    pub age: Option<i32>,
+}
+
+impl UserInfo {
+    pub const TABLE_NAME: &str = "user_info";
+}
+
+pub struct Legacy {
+    pub id: i64,
+}
+
+impl Legacy {
+    pub const TABLE_NAME: &str = "legacy";
+}
+"#;
+
+    fn manifest(tables: &[(&str, &[(&str, &str)])]) -> Manifest {
+        Manifest(
+            tables
+                .iter()
+                .map(|(table, columns)| {
+                    (
+                        table.to_string(),
+                        columns
+                            .iter()
+                            .map(|(c, t)| (c.to_string(), t.to_string()))
+                            .collect(),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn parses_generated_structs() {
+        assert_eq!(
+            parse(CODE).0,
+            manifest(&[
+                (
+                    "user_info",
+                    &[("id", "i64"), ("type", "String"), ("age", "Option<i32>")]
+                ),
+                ("legacy", &[("id", "i64")]),
+            ])
+            .0
+        );
+    }
+
+    #[test]
+    fn compares_columns() {
+        let database = manifest(&[
+            (
+                "user_info",
+                &[
+                    ("id", "i64"),
+                    ("type", "String"),
+                    ("age", "i64"),
+                    ("name", "String"),
+                ],
+            ),
+            ("orders", &[("id", "i64")]),
+        ]);
+        let diffs = compare(&parse(CODE), &database, false);
+        let summary = diffs
+            .iter()
+            .map(|d| {
+                format!(
+                    "{} {} {:?} {:?} {:?}",
+                    d.status,
+                    d.table,
+                    d.added,
+                    d.removed,
+                    d.retyped.iter().map(|c| c.to_string()).collect::<Vec<_>>()
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            [
+                r#"added orders ["id: i64"] [] []"#,
+                r#"changed user_info ["name: String"] [] ["user_info.age: Option<i32> -> i64"]"#,
+                r#"removed legacy [] ["id: i64"] []"#,
+            ]
+        );
+
+        // 只选择了部分表时不列出只在代码中的表
+        let diffs = compare(&parse(CODE), &database, true);
+        assert!(diffs.iter().all(|d| d.status != "removed"));
+    }
+}
@@ -0,0 +1,47 @@
+//! 表结构检查测试
+//!
+//! `--emit schema-tests` 时生成 schema_tests.rs，每张表一个测试：用 `SELECT * ... LIMIT 0` 读取数据库中表的列，
+//! 与结构体的 `COLUMNS` 比较列数和列名，数据库中增加、删除、重命名列后没有重新生成时 `cargo test` 失败。
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::{Generator, Table};
+
+/// 单张表的测试
+#[derive(Debug, Clone, Serialize)]
+pub struct SchemaTest {
+    /// 测试函数名称，与模块名相同
+    pub name: String,
+    /// 表结构体名称
+    pub struct_name: String,
+    /// 读取列的 SQL，为已转义的字符串字面量
+    pub sql: String,
+    /// 表所属的 feature
+    pub feature: Option<String>,
+}
+
+/// 按 mod.rs 中模块的顺序收集每张表的测试
+pub(crate) fn collect(
+    generator: &Generator,
+    table_map: &HashMap<String, Table>,
+) -> anyhow::Result<Vec<SchemaTest>> {
+    generator
+        .module_sections(table_map)
+        .iter()
+        .flat_map(|s| s.tables.iter())
+        .map(|table_name| {
+            Ok(SchemaTest {
+                name: table_name.clone(),
+                struct_name: generator.struct_name(table_name),
+                // sql_table_name 已经按字符串常量转义
+                sql: format!(
+                    r#""SELECT * FROM {} LIMIT 0""#,
+                    generator.sql_table_name(&table_map[table_name])?
+                ),
+                feature: generator.table_feature(table_name).map(str::to_string),
+            })
+        })
+        .collect()
+}
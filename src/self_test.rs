@@ -0,0 +1,109 @@
+//! 生成代码的编译检查
+//!
+//! 将生成的代码放入一个临时的 crate 中执行 `cargo clippy -- -D warnings`，
+//! 用于 `--self-test` 和本仓库的模板编译测试（tests/golden.rs）。
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::requirements::Requirements;
+
+/// 生成代码所在的目录，相对于临时 crate 的根目录
+pub const MODELS_DIR: &str = "src/models/";
+
+/// 临时 crate 的目录
+pub fn temp_dir(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("sqlx-db-cli-{name}-{}", std::process::id()))
+}
+
+/// 在 dir 下创建引用生成代码的 crate，生成代码需要已经写入 dir/src/models/
+pub fn scaffold(dir: &Path, requirements: &Requirements) -> anyhow::Result<()> {
+    // 临时 crate 不属于任何工作区
+    fs::write(
+        dir.join("Cargo.toml"),
+        format!(
+            "{}\n[workspace]\n",
+            manifest("sqlx-db-cli-self-test", requirements, true)
+        ),
+    )?;
+    fs::write(dir.join("src/lib.rs"), lib_rs("models"))?;
+    Ok(())
+}
+
+/// 生成代码依赖的 crate 的 Cargo.toml，default_features 时默认开启生成的代码使用的 feature，以便一起检查
+pub fn manifest(name: &str, requirements: &Requirements, default_features: bool) -> String {
+    let mut manifest = format!(
+        r#"[package]
+name = "{name}"
+version = "0.0.0"
+edition = "2021"
+publish = false
+
+[dependencies]
+{}"#,
+        requirements.dependencies()
+    );
+    let features = requirements.features();
+    if !features.is_empty() {
+        manifest.push_str("\n[features]\n");
+        if default_features {
+            let names = features
+                .lines()
+                .filter_map(|l| l.split_once(" = "))
+                .map(|(name, _)| format!("{name:?}"))
+                .collect::<Vec<_>>();
+            manifest.push_str(&format!("default = [{}]\n", names.join(", ")));
+        }
+        manifest.push_str(&features);
+    }
+    manifest
+}
+
+/// 引用 src 下 module 目录中生成代码的 lib.rs，
+/// 生成的代码通过 crate::error、crate::result 引用错误类型
+pub fn lib_rs(module: &str) -> String {
+    format!(
+        r#"#[path = "{module}/error.rs"]
+pub mod error;
+pub mod {module};
+#[path = "{module}/result.rs"]
+pub mod result;
+"#
+    )
+}
+
+/// 执行 cargo clippy，有错误或警告时返回 clippy 的输出
+pub fn clippy(dir: &Path) -> anyhow::Result<()> {
+    let output = Command::new(std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string()))
+        .args(["clippy", "--quiet", "--all-targets", "--", "-D", "warnings"])
+        .current_dir(dir)
+        .output()?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "生成的代码没有通过 clippy 检查（{}）：\n{}",
+            dir.display(),
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    Ok(())
+}
+
+/// 执行 cargo test，失败时返回测试的输出，用于检查生成代码的行为
+pub fn test(dir: &Path) -> anyhow::Result<()> {
+    let output = Command::new(std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string()))
+        .args(["test", "--quiet"])
+        .current_dir(dir)
+        .output()?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "生成的代码没有通过测试（{}）：\n{}{}",
+            dir.display(),
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,323 @@
+//! HTTP 服务模式
+//!
+//! 没有数据库访问权限的成员可以通过统一部署的服务（使用只读账号）浏览表结构、预览和下载生成的代码：
+//!
+//! - `GET /`                 表列表页面
+//! - `GET /tables`           表列表（JSON）
+//! - `GET /tables/{表名}`    预览该表生成的代码
+//! - `GET /bundle.zip`       下载全部生成的代码
+//!
+//! 每次请求都会重新读取表结构，预览时只渲染请求的表；下载时生成到临时目录后读取，不会写入输出目录。
+//! 表名在链接中按百分号编码，请求路径解码后再查找。
+//! 读取请求和写入响应超过 [`IO_TIMEOUT`] 时断开连接；生成出错时响应中只返回通用的错误信息，详情输出到日志。
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{Cursor, Write},
+    path::Path,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+use zip::{write::FileOptions, CompressionMethod, ZipWriter};
+
+use crate::{Generator, Table};
+
+/// 请求头的最大长度
+const MAX_HEAD: usize = 8 * 1024;
+
+/// 读取请求头、写入响应的超时时间，避免慢速或不发送数据的连接一直占用
+const IO_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 临时目录的序号，避免并发请求使用同一目录
+static SEQ: AtomicUsize = AtomicUsize::new(0);
+
+struct Response {
+    status: &'static str,
+    content_type: &'static str,
+    headers: Vec<(&'static str, String)>,
+    body: Vec<u8>,
+}
+
+impl Response {
+    fn new(status: &'static str, content_type: &'static str, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            content_type,
+            headers: vec![],
+            body: body.into(),
+        }
+    }
+
+    fn text(status: &'static str, body: impl Into<Vec<u8>>) -> Self {
+        Self::new(status, "text/plain; charset=utf-8", body)
+    }
+}
+
+/// 启动服务，直到进程退出
+pub async fn run(generator: Generator, addr: &str) -> anyhow::Result<()> {
+    let listener = TcpListener::bind(addr).await?;
+    tracing::info!("====== serve ======");
+    tracing::info!("listening on http://{}", listener.local_addr()?);
+
+    let generator = Arc::new(generator);
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let generator = generator.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle(&generator, stream).await {
+                tracing::error!("serve error: {e}");
+            }
+        });
+    }
+}
+
+async fn handle(generator: &Generator, mut stream: TcpStream) -> anyhow::Result<()> {
+    let head = tokio::time::timeout(IO_TIMEOUT, read_head(&mut stream))
+        .await
+        .map_err(|_| anyhow::anyhow!("读取请求超时"))??;
+    let Some(head) = head else {
+        return Ok(());
+    };
+
+    let head = String::from_utf8_lossy(&head);
+    let mut parts = head.lines().next().unwrap_or_default().split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let target = parts.next().unwrap_or_default();
+    let path = target.split('?').next().unwrap_or_default();
+
+    let response = match method {
+        "GET" => route(generator, path).await.unwrap_or_else(|e| {
+            // 错误中可能有连接地址、表结构等信息，只输出到日志
+            tracing::error!("{method} {target}: {e:?}");
+            Response::text("500 Internal Server Error", "生成失败，详情见服务日志")
+        }),
+        _ => Response::text("405 Method Not Allowed", "只支持 GET 请求"),
+    };
+    tracing::info!("{method} {target} {}", response.status);
+
+    let mut out = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+        response.status,
+        response.content_type,
+        response.body.len()
+    );
+    for (name, value) in response.headers.iter() {
+        out.push_str(&format!("{name}: {value}\r\n"));
+    }
+    out.push_str("\r\n");
+    let write = async {
+        stream.write_all(out.as_bytes()).await?;
+        stream.write_all(&response.body).await?;
+        stream.shutdown().await
+    };
+    tokio::time::timeout(IO_TIMEOUT, write)
+        .await
+        .map_err(|_| anyhow::anyhow!("写入响应超时"))??;
+    Ok(())
+}
+
+/// 读取请求头，连接在读完之前关闭时返回 None
+async fn read_head(stream: &mut TcpStream) -> anyhow::Result<Option<Vec<u8>>> {
+    let mut head = vec![];
+    let mut buf = [0; 1024];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+        let n = stream.read(&mut buf).await?;
+        if n == 0 {
+            return Ok(None);
+        }
+        head.extend_from_slice(&buf[..n]);
+        if head.len() > MAX_HEAD {
+            anyhow::bail!("请求头过长");
+        }
+    }
+    Ok(Some(head))
+}
+
+async fn route(generator: &Generator, path: &str) -> anyhow::Result<Response> {
+    let response = match path {
+        "/" => Response::new(
+            "200 OK",
+            "text/html; charset=utf-8",
+            index(&tables(generator).await?),
+        ),
+        "/tables" => Response::new(
+            "200 OK",
+            "application/json",
+            serde_json::to_vec(&tables(generator).await?)?,
+        ),
+        "/bundle.zip" => {
+            let mut response = Response::new("200 OK", "application/zip", bundle(generator).await?);
+            response.headers.push((
+                "Content-Disposition",
+                format!("attachment; filename=\"{}.zip\"", generator.database_name()),
+            ));
+            response
+        }
+        _ => match path.strip_prefix("/tables/").map(percent_decode) {
+            Some(table) => match preview(generator, &table).await? {
+                Some(contents) => Response::text("200 OK", contents),
+                None => Response::text("404 Not Found", format!("表 {table} 不存在")),
+            },
+            None => Response::text("404 Not Found", "Not Found"),
+        },
+    };
+    Ok(response)
+}
+
+async fn tables(generator: &Generator) -> anyhow::Result<Vec<Table>> {
+    let (tables, _) = generator.prepare().await?;
+    Ok(tables)
+}
+
+/// 表列表页面
+fn index(tables: &[Table]) -> String {
+    let rows = tables
+        .iter()
+        .map(|t| {
+            format!(
+                "<tr><td><a href=\"/tables/{href}\">{name}</a></td><td>{comment}</td></tr>",
+                href = percent_encode(&t.name),
+                name = escape(&t.name),
+                comment = escape(&t.comment)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head><meta charset="utf-8"><title>sqlx-db-cli</title></head>
+<body>
+<p><a href="/bundle.zip">下载全部代码</a></p>
+<table>
+<tr><th>表名</th><th>注释</th></tr>
+{rows}
+</table>
+</body>
+</html>
+"#
+    )
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// 链接中的表名，除字母、数字和 -._~ 外按 UTF-8 字节编码为 %XX
+fn percent_encode(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+/// 解码请求路径中的 %XX，不是合法的 %XX 时原样保留
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .filter(|h| h.iter().all(u8::is_ascii_hexdigit))
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(b)) => {
+                decoded.push(b);
+                i += 3;
+            }
+            (b, _) => {
+                decoded.push(b);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// 只渲染一张表的代码，表不存在时返回 None
+async fn preview(generator: &Generator, table: &str) -> anyhow::Result<Option<String>> {
+    let (tables, mut columns) = generator.prepare().await?;
+    let Some(table) = tables.into_iter().find(|t| t.name == table) else {
+        return Ok(None);
+    };
+    columns.retain(|c| {
+        c.table_name.as_deref() == Some(table.name.as_str())
+            && c.schema.as_ref().is_none_or(|s| s == &table.schema)
+    });
+    generator.map_types(&mut columns)?;
+    generator.render_table(&table, &columns).map(Some)
+}
+
+/// 生成全部代码，K：相对路径，V：文件内容
+async fn generate(generator: &Generator) -> anyhow::Result<BTreeMap<String, String>> {
+    let dir = std::env::temp_dir().join(format!(
+        "sqlx-db-cli-serve-{}-{}",
+        std::process::id(),
+        SEQ.fetch_add(1, Ordering::Relaxed)
+    ));
+    // 所有输出都改到临时目录中，不能写入配置中的输出目录、文件或标准输出
+    let mut generator = generator.clone();
+    generator.path = format!("{}/", dir.display());
+    generator.dry_run = false;
+    generator.stdout = false;
+    generator.save_catalog = None;
+
+    let result = async {
+        let (tables, mut columns) = generator.prepare().await?;
+        generator.map_types(&mut columns)?;
+        generator.write(tables, columns).await?;
+        let mut files = BTreeMap::new();
+        read_dir(&dir, &dir, &mut files)?;
+        Ok(files)
+    }
+    .await;
+    if dir.exists() {
+        fs::remove_dir_all(&dir)?;
+    }
+    result
+}
+
+fn read_dir(root: &Path, dir: &Path, files: &mut BTreeMap<String, String>) -> anyhow::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            read_dir(root, &path, files)?;
+        } else {
+            let name = path
+                .strip_prefix(root)?
+                .to_string_lossy()
+                .replace('\\', "/");
+            files.insert(name, fs::read_to_string(&path)?);
+        }
+    }
+    Ok(())
+}
+
+/// 打包全部代码
+async fn bundle(generator: &Generator) -> anyhow::Result<Vec<u8>> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = FileOptions::default().compression_method(CompressionMethod::Stored);
+    for (name, contents) in generate(generator).await? {
+        zip.start_file(name, options)?;
+        zip.write_all(contents.as_bytes())?;
+    }
+    Ok(zip.finish()?.into_inner())
+}
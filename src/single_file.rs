@@ -0,0 +1,61 @@
+//! `--single-file`：所有表生成到一个文件中
+//!
+//! mod.rs 中的 `mod 表名;` 替换为内联的 `mod 表名 { ... }`，error.rs、result.rs 作为 `pub mod error`、`pub mod result`
+//! 放在文件末尾，生成的代码通过 `super::error`、`super::result` 引用错误类型，文件可以直接作为一个模块放入项目中。
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::pipeline::{OutputFile, Rendered};
+
+/// 输出到标准输出时的文件名
+pub const STDOUT: &str = "-";
+
+/// 将一个输出目录中的文件合并为一个文件，MANIFEST.toml、REQUIREMENTS.toml 等非 Rust 文件不写入
+pub fn combine(rendered: Rendered, file: &str) -> anyhow::Result<Rendered> {
+    lazy_static! {
+        static ref MOD: Regex = Regex::new(r"(?m)^(pub )?mod (\w+);$").unwrap();
+    }
+    if !rendered.files.is_empty() || rendered.dirs.len() > 1 {
+        anyhow::bail!("--single-file 不能与 --crate-target、含有 {{schema}} 的输出路径同时使用");
+    }
+    let Some(dir) = rendered.dirs.into_iter().next() else {
+        return Ok(Rendered {
+            failed: rendered.failed,
+            ..Default::default()
+        });
+    };
+    let contents_of = |name: &str| {
+        dir.files
+            .iter()
+            .find(|f| f.path == format!("{}{name}.rs", dir.path))
+            .map(|f| f.contents.trim())
+    };
+    let mod_rs = contents_of("mod").unwrap_or_default();
+    let mut contents = MOD
+        .replace_all(mod_rs, |caps: &regex::Captures| {
+            match contents_of(&caps[2]) {
+                Some(module) => format!(
+                    "{}mod {} {{\n{module}\n}}",
+                    caps.get(1).map_or("", |m| m.as_str()),
+                    &caps[2]
+                ),
+                None => caps[0].to_string(),
+            }
+        })
+        .to_string();
+    for name in ["error", "result"] {
+        if let Some(module) = contents_of(name) {
+            contents.push_str(&format!("\n\npub mod {name} {{\n{module}\n}}"));
+        }
+    }
+    contents.push('\n');
+    Ok(Rendered {
+        files: vec![OutputFile {
+            path: file.to_string(),
+            contents,
+        }],
+        dirs: vec![],
+        failed: rendered.failed,
+    })
+}
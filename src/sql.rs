@@ -0,0 +1,262 @@
+//! 生成期构建的静态SQL语句
+//!
+//! 在生成代码时直接拼好完整的SQL，以 `const` 的形式输出到模型中，
+//! 保证语句文本稳定，便于预编译语句缓存命中，也方便代码评审。
+
+use serde::Serialize;
+
+use crate::{Column, Driver};
+
+/// 模型中使用的静态SQL
+#[derive(Serialize, Default, Debug, Clone)]
+pub struct Statements {
+    pub columns: String,
+    pub select_all: String,
+    pub select_by_id: String,
+    pub insert: String,
+    pub update_by_id: String,
+    pub delete_by_id: String,
+    pub count: String,
+}
+
+impl Statements {
+    pub fn new(driver: Driver, table_name: &str, columns: &[&Column]) -> Self {
+        let names = columns.iter().map(|c| raw_name(c)).collect::<Vec<_>>();
+        let column_names = names.join(",");
+        let selected = columns
+            .iter()
+            .map(|c| selected_column(c))
+            .collect::<Vec<_>>()
+            .join(",");
+        let id = columns.iter().find(|c| raw_name(c) == "id");
+        let id_value = |i| match id {
+            Some(id) => value(driver, id, i),
+            None => placeholder(driver, i),
+        };
+
+        // 生成列、由数据库生成 UUID 的主键的值由数据库计算，只查询，不写入
+        let inserted = columns
+            .iter()
+            .filter(|c| !c.is_generated() && !c.has_uuid_default())
+            .collect::<Vec<_>>();
+        let inserted_names = inserted
+            .iter()
+            .map(|c| raw_name(c))
+            .collect::<Vec<_>>()
+            .join(",");
+        let values = inserted
+            .iter()
+            .enumerate()
+            .map(|(i, c)| value(driver, c, i + 1))
+            .collect::<Vec<_>>()
+            .join(",");
+        // 由数据库自动更新的列只查询，不写入
+        let updated = columns
+            .iter()
+            .filter(|c| !c.is_auto_updated() && !c.is_generated())
+            .collect::<Vec<_>>();
+        let sets = updated
+            .iter()
+            .enumerate()
+            .map(|(i, c)| format!("{} = {}", raw_name(c), value(driver, c, i + 1)))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        Self {
+            select_all: format!("SELECT {selected} FROM {table_name}"),
+            select_by_id: format!(
+                "SELECT {selected} FROM {table_name} WHERE id = {}",
+                id_value(1)
+            ),
+            insert: match driver {
+                // Postgres 没有 last_insert_id，插入后直接返回主键
+                Driver::Postgres => {
+                    format!(
+                        "INSERT INTO {table_name} ({inserted_names}) VALUES({values}) RETURNING {}",
+                        id.map_or("id".to_string(), |id| selected_column(id))
+                    )
+                }
+                Driver::Mysql | Driver::Sqlite => {
+                    format!("INSERT INTO {table_name} ({inserted_names}) VALUES({values})")
+                }
+            },
+            update_by_id: format!(
+                "UPDATE {table_name} SET {sets} WHERE id = {}",
+                id_value(updated.len() + 1)
+            ),
+            delete_by_id: format!("DELETE FROM {table_name} WHERE id = {}", id_value(1)),
+            count: format!("SELECT count(*) FROM {table_name}"),
+            columns: column_names,
+        }
+    }
+}
+
+/// 查询的列，--portable 改变了类型时转换为新类型，如：CAST(age AS BIGINT) AS age
+fn selected_column(column: &Column) -> String {
+    match &column.cast {
+        Some(_) => format!("{} AS {}", filter_column(column), raw_name(column)),
+        None => raw_name(column).to_string(),
+    }
+}
+
+/// 查询条件中的列，--portable 改变了类型时与参数按新类型比较
+pub(crate) fn filter_column(column: &Column) -> String {
+    match &column.cast {
+        Some(cast) => format!("CAST({} AS {cast})", raw_name(column)),
+        None => raw_name(column).to_string(),
+    }
+}
+
+/// 写入列的参数，--portable 改变了类型时 Postgres 不会隐式转换字符串等参数，转换回列的类型
+fn value(driver: Driver, column: &Column, index: usize) -> String {
+    let placeholder = placeholder(driver, index);
+    match (driver, &column.cast, writable_type(column)) {
+        (Driver::Postgres, Some(_), Some(column_type)) => {
+            format!("CAST({placeholder} AS {column_type})")
+        }
+        _ => placeholder,
+    }
+}
+
+/// 可以在 CAST 中使用的列类型，information_schema 中的 ARRAY、USER-DEFINED 不是类型名称
+pub(crate) fn writable_type(column: &Column) -> Option<&str> {
+    column
+        .column_type
+        .as_deref()
+        .filter(|t| !matches!(t.to_uppercase().as_str(), "ARRAY" | "USER-DEFINED"))
+}
+
+/// 查询条件中时间、数值列的范围字段，如 created_at_from/created_at_to、amount_min/amount_max
+#[derive(Serialize, Debug, Clone)]
+pub struct RangeFilter {
+    /// SQL 中使用的列，--portable 改变了类型时带有 CAST
+    pub column: String,
+    /// 列注释，没有注释时为列名
+    pub comment: String,
+    /// 下限字段名
+    pub lower: String,
+    /// 上限字段名
+    pub upper: String,
+    pub field_type: String,
+    /// 是否为时间列，字段注释使用“起/止”
+    pub is_time: bool,
+    pub is_indexed: bool,
+}
+
+impl RangeFilter {
+    /// 主键和非时间、数值列没有范围字段
+    pub fn new(column: &Column) -> Option<Self> {
+        if column.is_primary_key {
+            return None;
+        }
+        let is_time = column.field_type.starts_with("time::") || column.field_type == "UtcDateTime";
+        let is_numeric = matches!(
+            column.field_type.as_str(),
+            "i8" | "i16"
+                | "i32"
+                | "i64"
+                | "u8"
+                | "u16"
+                | "u32"
+                | "u64"
+                | "f32"
+                | "f64"
+                | "bigdecimal::BigDecimal"
+        );
+        let (lower, upper) = match (is_time, is_numeric) {
+            (true, _) => ("from", "to"),
+            (_, true) => ("min", "max"),
+            _ => return None,
+        };
+
+        let field = column
+            .name
+            .as_deref()
+            .unwrap_or_default()
+            .trim_start_matches("r#");
+        Some(Self {
+            column: filter_column(column),
+            comment: column
+                .comment
+                .clone()
+                .filter(|c| !c.is_empty())
+                .unwrap_or_else(|| field.to_string()),
+            lower: format!("{field}_{lower}"),
+            upper: format!("{field}_{upper}"),
+            field_type: column.field_type.clone(),
+            is_time,
+            is_indexed: column.is_indexed,
+        })
+    }
+}
+
+/// 参数占位符，Postgres 使用 $n，其余使用 ?
+pub fn placeholder(driver: Driver, index: usize) -> String {
+    match driver {
+        Driver::Postgres => format!("${index}"),
+        Driver::Mysql | Driver::Sqlite => "?".to_string(),
+    }
+}
+
+/// SQL 中使用的列名，去掉关键字转义的 r# 前缀，字段由命名字典改名时使用原列名
+pub(crate) fn raw_name(column: &Column) -> &str {
+    column
+        .rename
+        .as_deref()
+        .or(column.name.as_deref())
+        .unwrap_or_default()
+        .trim_start_matches("r#")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping;
+
+    fn column(name: &str, column_type: &str, field_type: &str) -> Column {
+        Column {
+            name: Some(name.to_string()),
+            column_type: Some(column_type.to_string()),
+            field_type: field_type.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn casts_portable_columns() {
+        let mut id = column("id", "integer", "i32");
+        id.is_primary_key = true;
+        let mut token = column("token", "uuid", "uuid::Uuid");
+        let name = column("name", "varchar", "String");
+        mapping::portable(&mut id, Driver::Postgres);
+        mapping::portable(&mut token, Driver::Postgres);
+        assert_eq!(filter_column(&token), "CAST(token AS TEXT)");
+
+        let statements = Statements::new(Driver::Postgres, "users", &[&id, &token, &name]);
+        assert_eq!(statements.columns, "id,token,name");
+        assert_eq!(
+            statements.select_by_id,
+            "SELECT CAST(id AS BIGINT) AS id,CAST(token AS TEXT) AS token,name FROM users WHERE id = CAST($1 AS integer)"
+        );
+        assert_eq!(
+            statements.insert,
+            "INSERT INTO users (id,token,name) VALUES(CAST($1 AS integer),CAST($2 AS uuid),$3) RETURNING CAST(id AS BIGINT) AS id"
+        );
+        assert_eq!(
+            statements.update_by_id,
+            "UPDATE users SET id = CAST($1 AS integer), token = CAST($2 AS uuid), name = $3 WHERE id = CAST($4 AS integer)"
+        );
+    }
+
+    #[test]
+    fn casts_only_reads_for_mysql() {
+        let mut amount = column("amount", "int unsigned", "u32");
+        mapping::portable(&mut amount, Driver::Mysql);
+        let statements = Statements::new(Driver::Mysql, "orders", &[&amount]);
+        assert_eq!(
+            statements.select_all,
+            "SELECT CAST(amount AS SIGNED) AS amount FROM orders"
+        );
+        assert_eq!(statements.insert, "INSERT INTO orders (amount) VALUES(?)");
+    }
+}
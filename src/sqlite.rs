@@ -1,10 +1,15 @@
+use std::collections::HashMap;
+
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, Pool};
 
 /// 表信息来自 sqlite_master
 #[derive(Default, Debug, Serialize, Deserialize, FromRow)]
-struct Table {
+pub(crate) struct Table {
+    /// 所在的库：main 或 ATTACH 的库名
+    #[serde(default)]
+    schema: String,
     /// 项目的类型：table，index，view，trigger
     r#type: String,
     /// 项目的名称
@@ -19,7 +24,7 @@ struct Table {
 
 /// 表列信息
 #[derive(Default, Debug, Serialize, Deserialize, FromRow)]
-struct TableColumn {
+pub(crate) struct TableColumn {
     /// 列ID
     cid: Option<u32>,
     /// 列名
@@ -29,13 +34,31 @@ struct TableColumn {
     /// 是否为空：1-不为空，0-为空
     notnull: Option<u8>,
     dflt_value: Option<String>,
-    /// 是否为主键：1-主键，0-非主键
+    /// 在主键中的位置：0-非主键，大于0-主键
     pk: Option<u8>,
 }
 
+impl Table {
+    pub(crate) fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// 一张表的 pragma table_info、作为索引第一列的列名和建表语句
+#[derive(Default, Debug, Serialize, Deserialize)]
+pub(crate) struct TableInfo {
+    #[serde(default)]
+    schema: String,
+    table_name: String,
+    columns: Vec<TableColumn>,
+    indexed: Vec<String>,
+    create_sql: String,
+}
+
 impl From<Table> for super::Table {
     fn from(t: Table) -> Self {
         Self {
+            schema: schema_or_main(t.schema),
             name: t.name,
             ..Default::default()
         }
@@ -47,6 +70,8 @@ impl From<&TableColumn> for super::Column {
         let ty = sqlite_type(col.r#type.clone().unwrap().as_str());
         Self {
             name: Some(super::column_keywords(col.name.clone().as_str())),
+            // cid 从 0 开始
+            ordinal: col.cid.map(|cid| cid + 1).unwrap_or_default(),
             default: col.dflt_value.clone(),
             is_nullable: {
                 if let Some(is_null) = col.notnull {
@@ -55,6 +80,8 @@ impl From<&TableColumn> for super::Column {
                     true
                 }
             },
+            is_primary_key: col.pk.unwrap_or_default() > 0,
+            is_indexed: col.pk.unwrap_or_default() == 1,
             column_type: col.r#type.clone(),
             field_type: t2t(ty.0.as_str()).into(),
             multi_world: Some(super::multi_world(col.name.clone().as_str())),
@@ -85,7 +112,7 @@ impl From<&TableColumn> for super::Column {
 /// time::Time              TIME
 ///
 /// Sqlite类型转换为Rust类型
-fn t2t(ty: &str) -> &str {
+pub(crate) fn t2t(ty: &str) -> &str {
     match ty.to_uppercase().as_str() {
         "BOOLEAN" => "bool",
         "INTEGER" => "i32",
@@ -115,13 +142,34 @@ fn sqlite_type(t: &str) -> (String, Option<u16>) {
     }
 }
 
+/// 旧的目录数据中没有库名
+fn schema_or_main(schema: String) -> String {
+    match schema.is_empty() {
+        true => "main".to_string(),
+        false => schema,
+    }
+}
+
 pub async fn tables(
     pool: &Pool<sqlx::Sqlite>,
     table_names: &[&str],
 ) -> anyhow::Result<Vec<super::Table>> {
-    let mut sql =
-        "SELECT type, name, tbl_name, rootpage, sql FROM sqlite_master WHERE type = 'table'"
-            .to_string();
+    Ok(raw_tables(pool, "main", table_names)
+        .await?
+        .into_iter()
+        .map(|t| t.into())
+        .collect::<Vec<_>>())
+}
+
+/// schema 库的 sqlite_master 中的原始记录
+pub(crate) async fn raw_tables(
+    pool: &Pool<sqlx::Sqlite>,
+    schema: &str,
+    table_names: &[&str],
+) -> anyhow::Result<Vec<Table>> {
+    let mut sql = format!(
+        "SELECT '{schema}' AS schema, type, name, tbl_name, rootpage, sql FROM \"{schema}\".sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'"
+    );
 
     if !table_names.is_empty() {
         let table_names = table_names
@@ -132,36 +180,167 @@ pub async fn tables(
         sql.push_str(&format!(" AND name in({table_names}) "));
     }
 
-    Ok(sqlx::query_as::<_, Table>(&sql)
-        .fetch_all(pool)
-        .await?
-        .into_iter()
-        .map(|t| t.into())
-        .collect::<Vec<_>>())
+    Ok(sqlx::query_as::<_, Table>(&sql).fetch_all(pool).await?)
 }
 
 pub async fn columns(
     pool: &Pool<sqlx::Sqlite>,
     table_names: &[&str],
 ) -> anyhow::Result<Vec<super::Column>> {
-    let mut cols = vec![];
+    Ok(convert_columns(
+        raw_columns(pool, "main", table_names).await?,
+    ))
+}
+
+/// schema 库中每张表的原始列信息
+pub(crate) async fn raw_columns(
+    pool: &Pool<sqlx::Sqlite>,
+    schema: &str,
+    table_names: &[&str],
+) -> anyhow::Result<Vec<TableInfo>> {
+    let mut infos = vec![];
     for table_name in table_names.iter() {
-        let columns =
-            sqlx::query_as::<_, TableColumn>(&format!("pragma table_info('{}');", table_name))
-                .fetch_all(pool)
-                .await?;
+        let columns = sqlx::query_as::<_, TableColumn>(&format!(
+            "pragma \"{schema}\".table_info('{}');",
+            table_name
+        ))
+        .fetch_all(pool)
+        .await?;
+        let indexed = indexed_columns(pool, schema, table_name).await?;
+        let create_sql = sqlx::query_scalar::<_, Option<String>>(&format!(
+            "SELECT sql FROM \"{schema}\".sqlite_master WHERE type = 'table' AND name = ?"
+        ))
+        .bind(table_name)
+        .fetch_optional(pool)
+        .await?
+        .flatten()
+        .unwrap_or_default();
+        infos.push(TableInfo {
+            schema: schema.to_string(),
+            table_name: table_name.to_string(),
+            columns,
+            indexed,
+            create_sql,
+        });
+    }
+    Ok(infos)
+}
 
-        let mut columns = columns
+/// 原始列信息转换为列
+pub(crate) fn convert_columns(infos: Vec<TableInfo>) -> Vec<super::Column> {
+    let mut cols = vec![];
+    for info in infos {
+        let schema = schema_or_main(info.schema);
+        // Sqlite 没有查询列排序规则的 pragma，从建表语句中读取
+        let collations = crate::ddl::collations(&info.create_sql);
+        let indexed = info
+            .indexed
             .iter()
-            .map(|c| c.into())
-            .collect::<Vec<super::Column>>()
-            .iter_mut()
-            .map(|c| {
-                c.table_name = Some(table_name.to_string());
-                c.to_owned()
-            })
+            .map(|name| super::column_keywords(name))
             .collect::<Vec<_>>();
-        cols.append(&mut columns);
+        cols.extend(info.columns.iter().map(|c| {
+            let mut c: super::Column = c.into();
+            c.schema = Some(schema.clone());
+            c.table_name = Some(info.table_name.clone());
+            if indexed.iter().any(|i| Some(i) == c.name.as_ref()) {
+                c.is_indexed = true;
+            }
+            let name = c.name.as_deref().unwrap_or_default();
+            c.collation = collations.get(name.trim_start_matches("r#")).cloned();
+            c
+        }));
+    }
+    cols
+}
+
+/// 表的统计信息，K：表名，Sqlite 没有估算值，直接统计行数
+pub async fn stats(
+    pool: &Pool<sqlx::Sqlite>,
+    tables: &[super::Table],
+) -> anyhow::Result<HashMap<String, super::TableStats>> {
+    let mut stats = HashMap::new();
+    for table in tables {
+        let (rows,) = sqlx::query_as::<_, (i64,)>(&format!(
+            "SELECT count(*) FROM \"{}\".\"{}\"",
+            table.schema, table.name
+        ))
+        .fetch_one(pool)
+        .await?;
+        stats.insert(
+            table.name.clone(),
+            super::TableStats {
+                rows: Some(rows),
+                ..Default::default()
+            },
+        );
     }
-    Ok(cols)
+    Ok(stats)
+}
+
+/// 作为索引第一列的列名
+async fn indexed_columns(
+    pool: &Pool<sqlx::Sqlite>,
+    schema: &str,
+    table_name: &str,
+) -> anyhow::Result<Vec<String>> {
+    // seq, name, unique, origin, partial
+    let indexes = sqlx::query_as::<_, (i64, String, i64, String, i64)>(&format!(
+        "pragma \"{schema}\".index_list('{}');",
+        table_name
+    ))
+    .fetch_all(pool)
+    .await?;
+
+    let mut columns = vec![];
+    for (_, index_name, ..) in indexes {
+        // seqno, cid, name
+        let index_columns = sqlx::query_as::<_, (i64, i64, Option<String>)>(&format!(
+            "pragma \"{schema}\".index_info('{}');",
+            index_name
+        ))
+        .fetch_all(pool)
+        .await?;
+        columns.extend(
+            index_columns
+                .into_iter()
+                .filter(|(seqno, ..)| *seqno == 0)
+                .filter_map(|(.., name)| name),
+        );
+    }
+    Ok(columns)
+}
+
+/// 测试用的表结构样例
+#[cfg(feature = "test-support")]
+pub(crate) fn fixture() -> (Vec<super::Table>, Vec<super::Column>) {
+    let table = Table {
+        r#type: "table".to_string(),
+        name: "user".to_string(),
+        tbl_name: "user".to_string(),
+        ..Default::default()
+    };
+    let columns = [
+        ("id", "INTEGER", 1, 1),
+        ("name", "VARCHAR(50)", 1, 0),
+        ("email", "VARCHAR(255)", 0, 0),
+        ("amount", "REAL", 0, 0),
+        ("is_active", "BOOLEAN", 1, 0),
+        ("created_at", "DATETIME", 1, 0),
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(i, (name, ty, notnull, pk))| {
+        let mut column = super::Column::from(&TableColumn {
+            cid: Some(i as u32),
+            name: name.to_string(),
+            r#type: Some(ty.to_string()),
+            notnull: Some(notnull),
+            dflt_value: None,
+            pk: Some(pk),
+        });
+        column.table_name = Some("user".to_string());
+        column
+    })
+    .collect();
+    (vec![table.into()], columns)
 }
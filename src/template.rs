@@ -20,8 +20,17 @@ pub enum Error {
     JwtError(&'static str),
     #[error("服务器异常 : {0}")]
     ServerError(&'static str),
+    /// 其他数据库错误
     #[error("SQL错误")]
     SqlError,
+    #[error("记录不存在")]
+    NotFound,
+    /// 违反唯一约束，携带约束名称
+    #[error("数据重复: {0}")]
+    UniqueViolation(String),
+    /// 违反外键约束，携带约束名称
+    #[error("关联数据不存在或仍被引用: {0}")]
+    ForeignKeyViolation(String),
     #[error("未知错误")]
     Unknown,
 
@@ -32,59 +41,598 @@ pub enum Error {
     // #[error(transparent)]
     // AnyhowError(#[from] anyhow::Error),
 }
+
+impl From<sqlx::Error> for Error {
+    fn from(e: sqlx::Error) -> Self {
+        log::error!("{e}");
+        match &e {
+            sqlx::Error::RowNotFound => Self::NotFound,
+            sqlx::Error::Database(db) => {
+                let constraint = db
+                    .constraint()
+                    .map(|c| c.to_string())
+                    .unwrap_or_else(|| constraint_name(db.message()));
+                // 由驱动按 MySQL 的错误号、PostgreSQL 的 SQLSTATE、Sqlite 的扩展错误码分类
+                match db.kind() {
+                    sqlx::error::ErrorKind::UniqueViolation => Self::UniqueViolation(constraint),
+                    sqlx::error::ErrorKind::ForeignKeyViolation => {
+                        Self::ForeignKeyViolation(constraint)
+                    }
+                    _ => Self::SqlError,
+                }
+            }
+            _ => Self::SqlError,
+        }
+    }
+}
+
+/// 驱动没有直接提供约束名称时，从错误信息中提取
+fn constraint_name(message: &str) -> String {
+{% if driver == 'mysql' %}
+    // Duplicate entry '1' for key 'user.uk_email'
+    // ... a foreign key constraint fails (`db`.`t`, CONSTRAINT `fk_user` FOREIGN KEY ...
+    if let Some((_, key)) = message.rsplit_once("for key '") {
+        return key.trim_end_matches('\'').to_string();
+    }
+    if let Some((_, rest)) = message.split_once("CONSTRAINT `") {
+        return rest.split('`').next().unwrap_or_default().to_string();
+    }
+{% elif driver == 'sqlite' %}
+    // UNIQUE constraint failed: user.email
+    if let Some((_, columns)) = message.split_once("failed: ") {
+        return columns.to_string();
+    }
+{% endif %}
+    message.to_string()
+}
 "#;
 
 /// result.rs
 pub const RESULT_TEMPLATE: &str = r#"
-use crate::error::Error;
+use {{ error_module }}::error::Error;
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 "#;
 
+/// enums.rs
+pub const ENUM_TEMPLATE: &str = r#"
+use serde::{Deserialize, Serialize};
+{% for enum in all_enums %}
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    sqlx::Type,
+)]
+{% if driver == 'postgres' %}#[sqlx(type_name = "{{ enum.type_name }}")]{% endif %}
+pub enum {{ enum.name }} { {% for variant in enum.variants %}
+    {% if loop.first %}#[default]
+    {% endif %}#[serde(rename = {{ variant.literal }})]
+    #[sqlx(rename = {{ variant.literal }})]
+    {{ variant.name }},{% endfor %}
+}
+
+impl std::fmt::Display for {{ enum.name }} {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self { {% for variant in enum.variants %}
+            Self::{{ variant.name }} => {{ variant.literal }},{% endfor %}
+        })
+    }
+}
+
+impl std::str::FromStr for {{ enum.name }} {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s { {% for variant in enum.variants %}
+            {{ variant.literal }} => Ok(Self::{{ variant.name }}),{% endfor %}
+            _ => Err(format!("invalid {{ enum.name }}: {s}")),
+        }
+    }
+}
+{% endfor %}
+"#;
+
+/// binary.rs
+pub const BINARY_TEMPLATE: &str = r#"
+//! 二进制数据与字符串之间的转换，用于 Vec<u8> 字段的序列化
+{% if binary_encoding == "hex" %}
+pub mod hex {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn encode(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    pub fn decode(s: &str) -> Result<Vec<u8>, String> {
+        let s = s.strip_prefix("\\x").unwrap_or(s);
+        // 按字节切分，先排除非 ASCII 字符（会切在字符中间）和 from_str_radix 接受的正负号
+        if let Some(c) = s.chars().find(|c| !c.is_ascii_hexdigit()) {
+            return Err(format!("invalid hex character: {c}"));
+        }
+        if s.len() & 1 == 1 {
+            return Err(format!("invalid hex length: {}", s.len()));
+        }
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).map_err(|e| e.to_string()))
+            .collect()
+    }
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        decode(&String::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+    }
+}
+
+pub mod hex_option {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &Option<Vec<u8>>, serializer: S) -> Result<S::Ok, S::Error> {
+        match bytes {
+            Some(bytes) => serializer.serialize_some(&super::hex::encode(bytes)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Vec<u8>>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|s| super::hex::decode(&s).map_err(serde::de::Error::custom))
+            .transpose()
+    }
+}
+{% elif binary_encoding == "base64" %}
+pub mod base64 {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    pub fn encode(bytes: &[u8]) -> String {
+        let mut s = String::with_capacity(bytes.len().div_ceil(3) * 4);
+        for chunk in bytes.chunks(3) {
+            let n = chunk.iter().enumerate().fold(0u32, |n, (i, b)| n | (*b as u32) << (16 - i * 8));
+            for i in 0..4 {
+                if i <= chunk.len() {
+                    s.push(ALPHABET[(n >> (18 - i * 6)) as usize & 0x3f] as char);
+                } else {
+                    s.push('=');
+                }
+            }
+        }
+        s
+    }
+
+    pub fn decode(s: &str) -> Result<Vec<u8>, String> {
+        let s = s.trim_end_matches('=');
+        let mut bytes = Vec::with_capacity(s.len() * 3 / 4);
+        let mut n = 0u32;
+        for (i, c) in s.bytes().enumerate() {
+            let v = ALPHABET
+                .iter()
+                .position(|a| *a == c)
+                .ok_or_else(|| format!("invalid base64 character: {}", c as char))?;
+            n = n << 6 | v as u32;
+            if i % 4 == 3 {
+                bytes.extend_from_slice(&[(n >> 16) as u8, (n >> 8) as u8, n as u8]);
+                n = 0;
+            }
+        }
+        match s.len() % 4 {
+            2 => bytes.push((n >> 4) as u8),
+            3 => bytes.extend_from_slice(&[(n >> 10) as u8, (n >> 2) as u8]),
+            1 => return Err("invalid base64 length".to_string()),
+            _ => {}
+        }
+        Ok(bytes)
+    }
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        decode(&String::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+    }
+}
+
+pub mod base64_option {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &Option<Vec<u8>>, serializer: S) -> Result<S::Ok, S::Error> {
+        match bytes {
+            Some(bytes) => serializer.serialize_some(&super::base64::encode(bytes)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Vec<u8>>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|s| super::base64::decode(&s).map_err(serde::de::Error::custom))
+            .transpose()
+    }
+}
+{% endif %}
+"#;
+
 /// mod.rs 文件模板
-pub const MOD_TEMPLATE: &str = r#"
-use async_static::async_static;
+pub const MOD_TEMPLATE: &str = r#"{% for comment in schema_comments %}{% if not loop.first %}//!
+{% endif %}{% for line in comment | split(pat="
+") %}//!{% if line %} {{ line }}{% endif %}
+{% endfor %}{% endfor %}
+{% if not db_struct %}use async_static::async_static;{% endif %}
 use serde::{Deserialize, Serialize};
 
-{% if driver == 'Mysql' %}
+{% if driver == 'mysql' %}
 use sqlx::{MySql, Pool};
-{% elif driver == 'Postgres' %}
+{% elif driver == 'postgres' %}
 use sqlx::{Postgres, Pool}; 
-{% elif driver == 'Sqlite' %}
+{% elif driver == 'sqlite' %}
 use sqlx::{Sqlite, Pool};
 {% endif %}
 
 
-{% for table_name, _ in table_names %}
-mod {{ table_name }};
-pub use {{ table_name }}::*;
+{% for module in modules %}
+{% if module.name %}// {{ module.name }}
+{% elif modules | length > 1 %}// 其他
+{% endif %}{% for table_name in module.tables %}
+{% set feature = table_features | get(key=table_name, default="") %}
+{% if feature %}#[cfg(feature = "{{ feature }}")]
+{% endif %}mod {{ module_names | get(key=table_name) }};
+{% if feature %}#[cfg(feature = "{{ feature }}")]
+{% endif %}pub use {{ module_names | get(key=table_name) }}::*;
+{% endfor %}
 {% endfor %}
 
+{% if all_enums %}
+mod enums;
+pub use enums::*;
+{% endif %}
+
+{% if binary_encoding %}
+pub mod binary;
+{% endif %}
+
+{% if emit_events %}
+pub mod event_payloads;
+{% endif %}
+
+{% if emit_admin %}
+pub mod admin_views;
+{% endif %}
+
+{% if emit_axum %}
+pub mod extractors;
+{% endif %}
+
+{% if emit_schema_tests %}
+#[cfg(test)]
+mod schema_tests;
+{% endif %}
+
+{% if ci_string %}
+mod ci_string;
+pub use ci_string::CiString;
+{% endif %}
+
+{% if new_types %}
+mod udt;
+pub use udt::*;
+{% endif %}
+
+{% if utc_date_time %}
+mod utc_date_time;
+pub use utc_date_time::UtcDateTime;
+{% endif %}
+
+{% if has_must_filter %}
+/// 查询构造器状态：尚未设置带索引的查询条件
+pub struct Unfiltered;
+
+/// 查询构造器状态：已设置带索引的查询条件
+pub struct Filtered;
+{% endif %}
+
+{% if json_helpers %}
+/// JSON 中类型不严格的值的转换
+pub mod json_coerce {
+    use serde_json::Value;
+
+    /// 字符串形式的数字转换为数字，空字符串转换为 null
+    pub fn number(value: Value) -> Value {
+        let Value::String(s) = &value else {
+            return value;
+        };
+        let s = s.trim();
+        if s.is_empty() {
+            return Value::Null;
+        }
+        if let Ok(n) = s.parse::<i64>() {
+            return n.into();
+        }
+        if let Ok(n) = s.parse::<u64>() {
+            return n.into();
+        }
+        match s.parse::<f64>() {
+            Ok(n) => n.into(),
+            Err(_) => value,
+        }
+    }
+
+    /// 字符串 true/false/1/0 和数字 1/0 转换为布尔值，空字符串转换为 null
+    pub fn boolean(value: Value) -> Value {
+        match &value {
+            Value::String(s) => match s.trim().to_lowercase().as_str() {
+                "true" | "1" => Value::Bool(true),
+                "false" | "0" => Value::Bool(false),
+                "" => Value::Null,
+                _ => value,
+            },
+            Value::Number(n) if n.as_i64() == Some(1) => Value::Bool(true),
+            Value::Number(n) if n.as_i64() == Some(0) => Value::Bool(false),
+            _ => value,
+        }
+    }
+}
+{% endif %}
+
+{% if metrics %}
+/// 数据库操作的指标：应用实现 Metrics 并在启动时调用 set_metrics，生成的增删改查方法结束后通过 record 上报
+pub mod metrics {
+    use std::sync::OnceLock;
+    use std::time::{Duration, Instant};
+
+    /// 一次数据库操作
+    #[derive(Debug, Clone, Copy)]
+    pub struct Operation {
+        /// 表名
+        pub table: &'static str,
+        /// 方法名：fetch_by_id、fetch_all、page、insert、update、delete
+        pub method: &'static str,
+        /// 是否成功{% if not fetch_optional %}，fetch_by_id 没有找到行时为 false{% endif %}
+        pub success: bool,
+        /// 耗时
+        pub duration: Duration,
+    }
+
+    /// 由应用实现，如：用 metrics 的 counter!、histogram! 按表名、方法名、是否成功记录次数和耗时
+    pub trait Metrics: Send + Sync + 'static {
+        fn record(&self, operation: &Operation);
+    }
+
+    static METRICS: OnceLock<Box<dyn Metrics>> = OnceLock::new();
+
+    /// 设置指标的实现，只能设置一次，已经设置过时返回 false
+    pub fn set_metrics(metrics: impl Metrics) -> bool {
+        METRICS.set(Box::new(metrics)).is_ok()
+    }
+
+    /// 上报一次操作，没有设置实现时不做任何事
+    pub(crate) fn record<T, E>(table: &'static str, method: &'static str, start: Instant, result: &Result<T, E>) {
+        if let Some(metrics) = METRICS.get() {
+            metrics.record(&Operation {
+                table,
+                method,
+                success: result.is_ok(),
+                duration: start.elapsed(),
+            });
+        }
+    }
+}
+{% endif %}
+
+{% if any_decode_time %}
+/// sqlx::any 不支持时间类型，时间列以字符串解码后解析
+#[cfg(feature = "any")]
+pub mod any_decode {
+    use time::macros::format_description;
+    use time::{Date, OffsetDateTime, PrimitiveDateTime, Time};
+
+    /// 如：2024-01-02，带有时间时只取日期
+    pub fn date(s: &str) -> Result<Date, time::error::Parse> {
+        Date::parse(s.get(..10).unwrap_or(s), format_description!("[year]-[month]-[day]"))
+    }
+
+    /// 如：03:04:05、03:04:05.123
+    pub fn time(s: &str) -> Result<Time, time::error::Parse> {
+        Time::parse(s, format_description!("[hour]:[minute]:[second][optional [.[subsecond]]]"))
+    }
+
+    /// 日期和时间之间为空格或 T，如：2024-01-02 03:04:05.123
+    pub fn primitive_date_time(s: &str) -> Result<PrimitiveDateTime, time::error::Parse> {
+        PrimitiveDateTime::parse(
+            &s.replacen('T', " ", 1),
+            format_description!("[year]-[month]-[day] [hour]:[minute]:[second][optional [.[subsecond]]]"),
+        )
+    }
+
+    /// RFC 3339 或 PostgreSQL 的格式，如：2024-01-02T03:04:05Z、2024-01-02 03:04:05.123+08
+    pub fn offset_date_time(s: &str) -> Result<OffsetDateTime, time::error::Parse> {
+        OffsetDateTime::parse(
+            &s.replacen('T', " ", 1).replace('Z', "+00"),
+            format_description!(
+                "[year]-[month]-[day] [hour]:[minute]:[second][optional [.[subsecond]]][offset_hour sign:mandatory][optional [:[offset_minute]]]"
+            ),
+        )
+    }
+}
+{% endif %}
+
+{% if patch %}
+/// JSON Merge Patch（RFC 7396）中的字段：没有出现、为 null、有值
+#[derive(Debug, Default, Clone, PartialEq)]
+pub enum Patch<T> {
+    #[default]
+    Absent,
+    Null,
+    Value(T),
+}
+
+impl<T> Patch<T> {
+    pub fn is_absent(&self) -> bool {
+        matches!(self, Self::Absent)
+    }
+
+    /// 应用到可为空的字段，null 时清空
+    pub fn apply(self, target: &mut Option<T>) {
+        match self {
+            Self::Absent => {}
+            Self::Null => *target = None,
+            Self::Value(value) => *target = Some(value),
+        }
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Patch<T> {
+    /// 字段缺失时由 #[serde(default)] 得到 Absent
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        Option::<T>::deserialize(deserializer).map(|value| value.map_or(Self::Null, Self::Value))
+    }
+}
+
+impl<T: Serialize> Serialize for Patch<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        match self {
+            Self::Value(value) => value.serialize(serializer),
+            _ => serializer.serialize_none(),
+        }
+    }
+}
+{% endif %}
+
+{% if schema_check %}
+/// 生成代码时表结构的指纹，表结构变化后重新生成会改变
+pub const SCHEMA_FINGERPRINT: &str = "{{ schema_fingerprint }}";
+
+/// 启动时检查数据库中的表结构与生成代码时是否一致，不一致时返回所有差异{% if deprecated_attrs %}
+#[allow(deprecated)]{% endif %}
+pub async fn verify_schema(pool: &Pool<{{ sqlx_database | replace(from="sqlx::", to="") }}>) -> std::result::Result<(), Vec<String>> {
+    let mut errors = vec![];
+{% for module in modules %}{% for table_name in module.tables %}{% set feature = table_features | get(key=table_name, default="") %}
+    {% if feature %}#[cfg(feature = "{{ feature }}")]
+    {% endif %}errors.extend({{ module_names | get(key=table_name) }}::{{ struct_names | get(key=table_name) }}::verify_schema(pool).await);{% endfor %}{% endfor %}
+    match errors.is_empty() {
+        true => Ok(()),
+        false => Err(errors),
+    }
+}
+{% endif %}
+
+{% if db_struct %}
+/// 数据库连接，模型的方法通过 db.{表名}() 访问，如：db.user_info().fetch_by_id(1)，
+/// 可以用 Db::from(pool) 注入测试使用的连接池
+#[derive(Debug, Clone)]
+pub struct Db(pub Pool<{{ sqlx_database | replace(from="sqlx::", to="") }}>);
+
+impl Db {
+    /// 连接数据库
+    pub async fn connect(url: &str) -> std::result::Result<Self, sqlx::Error> {
+        Ok(Self(Pool::connect(url).await?))
+    }
+
+    pub fn pool(&self) -> &Pool<{{ sqlx_database | replace(from="sqlx::", to="") }}> {
+        &self.0
+    }
+{% for module in modules %}{% for table_name in module.tables %}{% set feature = table_features | get(key=table_name, default="") %}
+    {% if feature %}#[cfg(feature = "{{ feature }}")]
+    {% endif %}{% set attr = deprecated_attrs | get(key=table_name, default="") %}{% if attr %}{{ attr }}
+    #[allow(deprecated)]
+    {% endif %}pub fn {{ module_names | get(key=table_name) }}(&self) -> {{ module_names | get(key=table_name) }}::{{ struct_names | get(key=table_name) }}Repo<'_> {
+        {{ module_names | get(key=table_name) }}::{{ struct_names | get(key=table_name) }}Repo::new(&self.0)
+    }
+{% endfor %}{% endfor %}}
+
+impl From<Pool<{{ sqlx_database | replace(from="sqlx::", to="") }}>> for Db {
+    fn from(pool: Pool<{{ sqlx_database | replace(from="sqlx::", to="") }}>) -> Self {
+        Self(pool)
+    }
+}
+{% if emit_schema_tests %}
+/// 表结构检查测试使用的连接池
+#[cfg(test)]
+async fn pool() -> Pool<{{ sqlx_database | replace(from="sqlx::", to="") }}> {
+    let url = std::env::var("DATABASE_URL").expect("未设置环境变量 DATABASE_URL");
+{%- if attach_sql %}
+    sqlx::pool::PoolOptions::new()
+        .after_connect(|conn, _| {
+            Box::pin(async move {
+                use sqlx::Executor;
+{%- for sql in attach_sql %}
+                conn.execute({{ sql }}).await?;
+{%- endfor %}
+                Ok(())
+            })
+        })
+        .connect(&url)
+        .await
+        .unwrap()
+{%- else %}
+    Pool::connect(&url).await.unwrap()
+{%- endif %}
+}
+{% endif %}
+{% else %}
 async_static! {
-{% if driver == 'Mysql' %}
+{% if driver == 'mysql' %}
     static ref DB: Pool<MySql> = pool().await;
-{% elif driver == 'Postgres' %}
+{% elif driver == 'postgres' %}
     static ref DB: Pool<Postgres> = pool().await;    
-{% elif driver == 'Sqlite' %}
+{% elif driver == 'sqlite' %}
     static ref DB: Pool<Sqlite> = pool().await;    
 {% endif %}
 }
 
-{% if driver == 'Mysql' %}
+{% if driver == 'mysql' %}
 async fn pool() -> Pool<MySql> {
-    sqlx::MySqlPool::connect("{{ driver_url }}").await.unwrap()
+    let url = std::env::var("DATABASE_URL").expect("未设置环境变量 DATABASE_URL");
+    sqlx::MySqlPool::connect(&url).await.unwrap()
 }
-{% elif driver == 'Postgres' %}
+{% elif driver == 'postgres' %}
 async fn pool() -> Pool<Postgres> {
-    sqlx::PgPool::connect("{{ driver_url }}").await.unwrap()
+    let url = std::env::var("DATABASE_URL").expect("未设置环境变量 DATABASE_URL");
+    sqlx::PgPool::connect(&url).await.unwrap()
 }
-{% elif driver == 'Sqlite' %}
+{% elif driver == 'sqlite' %}
 async fn pool() -> Pool<Sqlite> {
-    sqlx::SqlitePool::connect("{{ driver_url }}").await.unwrap()
+    let url = std::env::var("DATABASE_URL").expect("未设置环境变量 DATABASE_URL");
+{%- if attach_sql %}
+    // 每个连接都要附加数据库
+    sqlx::sqlite::SqlitePoolOptions::new()
+        .after_connect(|conn, _| {
+            Box::pin(async move {
+                use sqlx::Executor;
+{%- for sql in attach_sql %}
+                conn.execute({{ sql }}).await?;
+{%- endfor %}
+                Ok(())
+            })
+        })
+        .connect(&url)
+        .await
+        .unwrap()
+{%- else %}
+    sqlx::SqlitePool::connect(&url).await.unwrap()
+{%- endif %}
 }
 {% endif %}
+{% endif %}
 
 
+/// 分页查询没有指定 page_size 时每页的条数
+pub const DEFAULT_PAGE_SIZE: i64 = {{ default_page_size }};
+/// 分页查询每页的最大条数
+pub const MAX_PAGE_SIZE: i64 = {{ max_page_size }};
+
 /// 分页返回封装
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct PageRes<T> {
@@ -103,7 +651,7 @@ impl<T> std::default::Default for PageRes<T> {
     fn default() -> Self {
         Self {
             page: 1,
-            page_size: 15,
+            page_size: DEFAULT_PAGE_SIZE,
             total: 0,
             list: vec![],
             first: true,
@@ -128,7 +676,7 @@ where
             page,
             page_size,
             total,
-            list: list.iter().cloned().collect::<Vec<_>>(),
+            list: list.to_vec(),
             first: page == 1,
             last: page == total_pages,
             has_next: page < total_pages,
@@ -139,20 +687,282 @@ where
 }
 "#;
 
-/// model模板
-pub const MODEL_TEMPLATE: &str = r#"
+/// event_payloads.rs
+pub const EVENT_TEMPLATE: &str = r#"
+//! 事件消息，只依赖 serde，用于将表中的行作为消息发布
+{% if deprecated_attrs %}#![allow(deprecated)]{% endif %}
+
 use serde::{Deserialize, Serialize};
-use sqlx::FromRow;
-use validator::Validate;
+{% for payload in event_payloads %}
+/// {{ payload.comment }}事件消息
+{% if payload.feature %}#[cfg(feature = "{{ payload.feature }}")]
+{% endif %}#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct {{ payload.struct_name }}Event { {% for column in payload.columns %}
+    /// {% if column.comment %}{{ column.comment }}{% else %}{{ column.name }}{% endif %}
+    {%if binary_encoding and column.field_type == "Vec<u8>" -%}#[serde(with = "super::binary::{{ binary_encoding }}{% if column.is_nullable %}_option{% endif %}")]{%- endif%}
+    pub {{ column.name }}: {% if column.is_nullable %}Option<{% endif %}{% if column.enum_values or column.field_type == "CiString" or column.field_type == "UtcDateTime" %}super::{% endif %}{{ column.field_type }}{% if column.is_nullable %}>{% endif %},{% endfor %}
+}
 
-use super::DB;
-use crate::{error::Error, result::Result};
+{% if payload.feature %}#[cfg(feature = "{{ payload.feature }}")]
+{% endif %}impl From<super::{{ payload.struct_name }}> for {{ payload.struct_name }}Event {
+    fn from({% if payload.columns %}model{% else %}_{% endif %}: super::{{ payload.struct_name }}) -> Self {
+        Self { {% for column in payload.columns %}
+            {{ column.name }}: model.{{ column.name }}{% if payload.id_newtype and column.name == "id" %}.0{% endif %},{% endfor %}
+        }
+    }
+}
+{% endfor %}
+"#;
+
+/// admin_views.rs
+pub const ADMIN_TEMPLATE: &str = r#"
+//! 管理后台的列表描述
+
+/// 表的描述
+#[derive(Debug, Clone, Copy)]
+pub struct TableDescriptor {
+    /// 表名
+    pub table: &'static str,
+    /// 显示名称
+    pub display_name: &'static str,
+    pub columns: &'static [ColumnDescriptor],
+}
 
-/// {{table.comment}}
+/// 列的描述
+#[derive(Debug, Clone, Copy)]
+pub struct ColumnDescriptor {
+    /// 列名
+    pub name: &'static str,
+    /// 结构体中的字段名
+    pub field: &'static str,
+    /// 标签
+    pub label: &'static str,
+    /// 可以作为查询条件（带索引）
+    pub searchable: bool,
+    /// 可以排序（带索引）
+    pub sortable: bool,
+    pub nullable: bool,
+}
+{% for table in admin_tables %}
+{% if table.feature %}#[cfg(feature = "{{ table.feature }}")]
+{% endif %}pub const {{ table.const_name }}: TableDescriptor = TableDescriptor {
+    table: {{ table.table }},
+    display_name: {{ table.display_name }},
+    columns: &[{% for column in table.columns %}
+        ColumnDescriptor {
+            name: {{ column.name }},
+            field: {{ column.field }},
+            label: {{ column.label }},
+            searchable: {{ column.searchable }},
+            sortable: {{ column.sortable }},
+            nullable: {{ column.nullable }},
+        },{% endfor %}
+    ],
+};
+{% endfor %}
+/// 全部表的描述
+pub const TABLES: &[TableDescriptor] = &[{% for table in admin_tables %}
+    {% if table.feature %}#[cfg(feature = "{{ table.feature }}")]
+    {% endif %}{{ table.const_name }},{% endfor %}
+];
+"#;
+
+/// extractors.rs
+pub const EXTRACTORS_TEMPLATE: &str = r#"
+//! axum 的提取器：反序列化请求后执行 validator::Validate，校验失败时返回 422
+//!
+//! ```ignore
+//! async fn create(ValidJson(model): ValidJson<UserInfo>) -> ... {}
+//! async fn list(ValidQuery(req): ValidQuery<UserInfoReq>) -> ... {}
+//! ```
+
+use std::collections::BTreeMap;
+
+use axum::{
+    async_trait,
+    extract::{
+        rejection::{JsonRejection, QueryRejection},
+        FromRequest, FromRequestParts, Query, Request,
+    },
+    http::{request::Parts, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use validator::{Validate, ValidationErrors};
+
+/// 校验失败的响应，状态码为 422
+#[derive(Debug, Clone, Serialize)]
+pub struct ValidationErrorResponse {
+    pub message: String,
+    /// K：字段名，V：没有通过的校验规则
+    pub errors: BTreeMap<String, Vec<String>>,
+}
+
+impl From<ValidationErrors> for ValidationErrorResponse {
+    fn from(e: ValidationErrors) -> Self {
+        let errors = e
+            .field_errors()
+            .into_iter()
+            .map(|(field, errors)| {
+                let codes = errors.iter().map(|e| e.code.to_string()).collect();
+                (field.to_string(), codes)
+            })
+            .collect();
+        Self {
+            message: "参数校验错误".to_string(),
+            errors,
+        }
+    }
+}
+
+impl IntoResponse for ValidationErrorResponse {
+    fn into_response(self) -> Response {
+        (StatusCode::UNPROCESSABLE_ENTITY, Json(self)).into_response()
+    }
+}
+
+/// 提取失败：请求无法反序列化时使用 axum 的响应，校验失败时返回 422
+#[derive(Debug)]
+pub enum ValidRejection<R> {
+    Extract(R),
+    Invalid(ValidationErrorResponse),
+}
+
+impl<R: IntoResponse> IntoResponse for ValidRejection<R> {
+    fn into_response(self) -> Response {
+        match self {
+            Self::Extract(rejection) => rejection.into_response(),
+            Self::Invalid(response) => response.into_response(),
+        }
+    }
+}
+
+/// 从 JSON 请求体反序列化并校验，用于新增、修改
+#[derive(Debug, Clone)]
+pub struct ValidJson<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for ValidJson<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = ValidRejection<JsonRejection>;
+
+    async fn from_request(req: Request, state: &S) -> std::result::Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(req, state)
+            .await
+            .map_err(ValidRejection::Extract)?;
+        value.validate().map_err(|e| ValidRejection::Invalid(e.into()))?;
+        Ok(Self(value))
+    }
+}
+
+/// 从查询参数反序列化并校验，用于列表查询的 Req
+#[derive(Debug, Clone)]
+pub struct ValidQuery<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for ValidQuery<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = ValidRejection<QueryRejection>;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> std::result::Result<Self, Self::Rejection> {
+        let Query(value) = Query::<T>::from_request_parts(parts, state)
+            .await
+            .map_err(ValidRejection::Extract)?;
+        value.validate().map_err(|e| ValidRejection::Invalid(e.into()))?;
+        Ok(Self(value))
+    }
+}
+"#;
+
+/// schema_tests.rs
+pub const SCHEMA_TESTS_TEMPLATE: &str = r#"
+//! 检查结构体的列与数据库中的表一致，数据库中增加、删除、重命名列后需要重新生成
+{% if deprecated_attrs %}#![allow(deprecated)]{% endif %}
+
+use sqlx::{Column, Executor, Statement};
+
+/// 比较表的列与结构体的列，不比较顺序
+async fn assert_columns(sql: &str, columns: &str) {
+    // 每个测试使用各自的运行时，不能共用 DB 中的连接池
+    let pool = super::pool().await;
+    let statement = pool.prepare(sql).await.unwrap();
+    let mut actual = statement.columns().iter().map(|c| c.name()).collect::<Vec<_>>();
+    let mut expected = columns.split(',').collect::<Vec<_>>();
+    actual.sort_unstable();
+    expected.sort_unstable();
+    assert_eq!(actual.len(), expected.len(), "{sql}: 列数不一致，数据库：{actual:?}，结构体：{expected:?}");
+    assert_eq!(actual, expected, "{sql}: 列名不一致");
+}
+{% for test in schema_tests %}
+{% if test.feature %}#[cfg(feature = "{{ test.feature }}")]
+{% endif %}#[tokio::test]
+async fn {{ test.name }}() {
+    assert_columns({{ test.sql }}, super::{{ test.struct_name }}::COLUMNS).await;
+}
+{% endfor %}
+"#;
+
+/// schema.rs
+pub const DIESEL_SCHEMA_TEMPLATE: &str = r#"
+//! Diesel 的表结构定义，与 sqlx 的模型来自同一份表结构，不在 mod.rs 中声明
+{% if diesel_schema.sql_types %}
+pub mod sql_types {
+{%- for t in diesel_schema.sql_types %}
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel({{ t.attr }})]
+    pub struct {{ t.name }};
+{% endfor -%}
+}
+{% endif %}
+{%- for table in diesel_schema.tables %}
+{% if table.feature %}#[cfg(feature = "{{ table.feature }}")]
+{% endif %}diesel::table! {
+    use diesel::sql_types::*;
+{%- for t in table.sql_types %}
+    use super::sql_types::{{ t }};
+{%- endfor %}
+
+{% for line in table.doc %}    /// {{ line }}
+{% endfor %}{% if table.sql_name %}    #[sql_name = "{{ table.sql_name }}"]
+{% endif %}    {{ table.name }} ({{ table.primary_key | join(sep=", ") }}) {
+{%- for column in table.columns %}
+{% for line in column.doc %}        /// {{ line }}
+{% endfor %}{% if column.sql_name %}        #[sql_name = "{{ column.sql_name }}"]
+{% endif %}        {{ column.name }} -> {{ column.sql_type }},
+{%- endfor %}
+    }
+}
+{% endfor %}
+{%- if diesel_schema.same_query | length > 1 %}
+diesel::allow_tables_to_appear_in_same_query!(
+{%- for ident in diesel_schema.same_query %}
+    {{ ident }},
+{%- endfor %}
+);
+{% endif %}
+"#;
+
+/// 按 UTC 存储的时间
+pub const UTC_DATE_TIME_TEMPLATE: &str = r#"
+//! 按 UTC 存储的时间，用于不带时区的时间列（MySQL DATETIME、PostgreSQL TIMESTAMP、Sqlite DATETIME）
+
+use std::{fmt, ops::Deref};
+
+use serde::{Deserialize, Serialize};
+
+/// 不带时区的时间，约定数据库中按 UTC 存储
 #[derive(
     Debug,
-    Default,
     Clone,
+    Copy,
     PartialEq,
     Eq,
     PartialOrd,
@@ -160,13 +970,321 @@ use crate::{error::Error, result::Result};
     Hash,
     Serialize,
     Deserialize,
-    FromRow,
-    Validate,
+    sqlx::Type,
 )]
+#[serde(transparent)]
+#[sqlx(transparent)]
+pub struct UtcDateTime(pub time::PrimitiveDateTime);
+
+impl UtcDateTime {
+    /// 当前的 UTC 时间
+    pub fn now() -> Self {
+        time::OffsetDateTime::now_utc().into()
+    }
+
+    /// 转换为带时区（UTC）的时间
+    pub fn assume_utc(self) -> time::OffsetDateTime {
+        self.0.assume_utc()
+    }
+}
+
+impl Deref for UtcDateTime {
+    type Target = time::PrimitiveDateTime;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl fmt::Display for UtcDateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl From<time::OffsetDateTime> for UtcDateTime {
+    fn from(t: time::OffsetDateTime) -> Self {
+        let t = t.to_offset(time::UtcOffset::UTC);
+        Self(time::PrimitiveDateTime::new(t.date(), t.time()))
+    }
+}
+
+impl From<UtcDateTime> for time::OffsetDateTime {
+    fn from(t: UtcDateTime) -> Self {
+        t.assume_utc()
+    }
+}
+"#;
+
+/// 自定义类型
+pub const UDT_TEMPLATE: &str = r#"
+//! 自定义类型（--type-map），包装基础类型并使用数据库中的类型名
+
+use serde::{Deserialize, Serialize};
+{% for type in new_types %}
+/// {{ type.type_name }}
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::Encode, sqlx::Decode)]
+#[serde(transparent)]
+pub struct {{ type.name }}(pub {{ type.inner }});
+
+// 域的列在结果中为基础类型，解码时同时接受基础类型
+impl sqlx::Type<sqlx::Postgres> for {{ type.name }} {
+    fn type_info() -> sqlx::postgres::PgTypeInfo {
+        sqlx::postgres::PgTypeInfo::with_name("{{ type.type_name }}")
+    }
+
+    fn compatible(ty: &sqlx::postgres::PgTypeInfo) -> bool {
+        *ty == Self::type_info() || <{{ type.inner }} as sqlx::Type<sqlx::Postgres>>::compatible(ty)
+    }
+}
+
+impl std::fmt::Display for {{ type.name }} {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::str::FromStr for {{ type.name }} {
+    type Err = <{{ type.inner }} as std::str::FromStr>::Err;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        s.parse().map(Self)
+    }
+}
+
+impl From<{{ type.inner }}> for {{ type.name }} {
+    fn from(v: {{ type.inner }}) -> Self {
+        Self(v)
+    }
+}
+{% endfor %}"#;
+
+/// 不区分大小写的字符串
+pub const CI_STRING_TEMPLATE: &str = r#"
+//! 不区分大小写的字符串，用于排序规则不区分大小写的列，比较结果与数据库一致
+
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    ops::Deref,
+};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, sqlx::Type)]
+#[serde(transparent)]
+#[sqlx(transparent)]
+pub struct CiString(pub String);
+
+impl CiString {
+    fn key(&self) -> impl Iterator<Item = char> + '_ {
+        self.0.chars().flat_map(char::to_lowercase)
+    }
+}
+
+impl PartialEq for CiString {
+    fn eq(&self, other: &Self) -> bool {
+        self.key().eq(other.key())
+    }
+}
+
+impl Eq for CiString {}
+
+impl PartialOrd for CiString {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for CiString {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(other.key())
+    }
+}
+
+impl Hash for CiString {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for c in self.key() {
+            c.hash(state);
+        }
+    }
+}
+
+impl Deref for CiString {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for CiString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<String> for CiString {
+    fn from(s: String) -> Self {
+        Self(s)
+    }
+}
+
+impl From<&str> for CiString {
+    fn from(s: &str) -> Self {
+        Self(s.to_string())
+    }
+}
+
+impl From<CiString> for String {
+    fn from(s: CiString) -> Self {
+        s.0
+    }
+}
+"#;
+
+/// --from-query 查询结果的模板，只有结构体和执行查询的函数
+pub const QUERY_TEMPLATE: &str = r#"//! {{ table.comment }}
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+{% if not db_struct %}use super::DB;{% endif %}
+{% if uses_ci_string %}use super::CiString;{% endif %}
+{% if uses_utc_date_time %}use super::UtcDateTime;{% endif %}
+use {{ error_module }}::{error::Error, result::Result};
+
+/// {{ table.comment }}
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
 #[serde(rename_all(serialize = "camelCase"))]
+pub struct {{ struct_name }} { {% for column in columns %}
+    /// {{ column.comment }}{% if column.example %}
+    ///
+    /// example: {{ column.example }}{% endif %}{% if column.lossy %}
+    ///
+    /// WARNING: {{ column.lossy }}{% endif %}
+    {%if binary_encoding and column.field_type == "Vec<u8>" -%}#[serde(with = "super::binary::{{ binary_encoding }}{% if column.is_nullable %}_option{% endif %}")]{%- endif%}
+    {% if column.rename %}#[sqlx(rename = "{{ column.rename }}")]{% endif %}
+    pub {{column.name}}: {%if column.is_nullable %}Option<{{column.field_type}}>{% else %}{{column.field_type}}{% endif %},{% endfor %}
+}
+
+impl {{ struct_name }} {
+    /// 查询语句，带参数时使用 sqlx::query_as::<_, {{ struct_name }}>(Self::SQL).bind(...) 执行
+    pub const SQL: &'static str = {{ query_literal }};
+
+    /// 执行不带参数的查询
+    pub async fn fetch_all({% if db_struct %}pool: &sqlx::Pool<{{ sqlx_database }}>{% endif %}) -> Result<Vec<Self>> {
+        sqlx::query_as::<_, Self>(Self::SQL)
+            .fetch_all({% if db_struct %}pool{% else %}DB.await{% endif %})
+            .await
+            .map_err(Error::from)
+    }
+}
+{% if db_struct %}
+/// {{ table.comment }}，通过 db.{{ table.name }}() 获取
+#[derive(Debug, Clone, Copy)]
+pub struct {{ struct_name }}Repo<'a> {
+    pool: &'a sqlx::Pool<{{ sqlx_database }}>,
+}
+
+impl<'a> {{ struct_name }}Repo<'a> {
+    pub fn new(pool: &'a sqlx::Pool<{{ sqlx_database }}>) -> Self {
+        Self { pool }
+    }
+
+    /// 执行不带参数的查询
+    pub async fn fetch_all(&self) -> Result<Vec<{{ struct_name }}>> {
+        {{ struct_name }}::fetch_all(self.pool).await
+    }
+}
+{% endif %}"#;
+
+/// model模板
+pub const MODEL_TEMPLATE: &str = r#"{% if table.comment %}{% set module_doc = table.comment %}{% else %}{% set module_doc = table.name %}{% endif %}{% if db_struct %}{% set pool = "pool" %}{% set pool_arg = "pool, " %}{% set pool_param = "pool: &sqlx::Pool<" ~ sqlx_database ~ ">, " %}{% set pool_tail = ", pool: &sqlx::Pool<" ~ sqlx_database ~ ">" %}{% else %}{% set pool = "DB.await" %}{% set pool_arg = "" %}{% set pool_param = "" %}{% set pool_tail = "" %}{% endif %}{% for line in module_doc | split(pat="
+") %}//!{% if line %} {{ line }}{% endif %}
+{% endfor %}{% if deprecated_attr %}#![allow(deprecated)]
+{% endif %}
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use validator::Validate;
+
+{% if not db_struct %}use super::DB;{% endif %}
+{% if enums %}use super::enums::{ {% for enum in enums %}{{ enum.name }}, {% endfor %}};{% endif %}
+{% if uses_ci_string %}use super::CiString;{% endif %}
+{% if uses_utc_date_time %}use super::UtcDateTime;{% endif %}
+use {{ error_module }}::{error::Error, result::Result};
+{% if id_newtype %}
+/// {% if table.comment %}{{ table.comment }}{% else %}{{ table.name }}{% endif %}的主键
+#[derive(Debug, Default, Clone,{% if id_newtype.copy %} Copy,{% endif %} PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, sqlx::Type)]
+#[serde(transparent)]
+#[sqlx(transparent)]
+pub struct {{ id_newtype.name }}(pub {{ id_newtype.inner }});
+
+impl std::fmt::Display for {{ id_newtype.name }} {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::str::FromStr for {{ id_newtype.name }} {
+    type Err = <{{ id_newtype.inner }} as std::str::FromStr>::Err;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        s.parse().map(Self)
+    }
+}
+
+impl From<{{ id_newtype.inner }}> for {{ id_newtype.name }} {
+    fn from(id: {{ id_newtype.inner }}) -> Self {
+        Self(id)
+    }
+}
+
+impl From<{{ id_newtype.name }}> for {{ id_newtype.inner }} {
+    fn from(id: {{ id_newtype.name }}) -> Self {
+        id.0
+    }
+}
+{% endif %}
+/// {% if table.comment %}{{ table.comment }}{% else %}{{ table.name }}{% endif %}{% if table.stats and table.stats.rows %}
+///
+/// 约 {{ table.stats.rows }} 行数据{% if table.stats.rows > 1000000 %}，避免无索引的全表扫描{% endif %}{% endif %}{% if table.constraints %}
+///
+/// 约束：{% for constraint in table.constraints %}
+/// - `{{ constraint.name }}` {{ constraint.definition }}{% endfor %}{% endif %}{% if deprecated_attr %}
+{{ deprecated_attr }}{% endif %}
+#[derive(
+    Debug,{% if derive_default %}
+    Default,{% endif %}
+    Clone,
+    PartialEq,{% if derive_eq %}
+    Eq,{% endif %}{% if derive_partial_ord %}
+    PartialOrd,{% endif %}{% if derive_eq and derive_partial_ord %}
+    Ord,{% endif %}{% if derive_eq %}
+    Hash,{% endif %}
+    Serialize,
+    Deserialize,{% if not wide %}
+    FromRow,{% endif %}
+    Validate,
+)]
+#[serde(rename_all(serialize = "camelCase"){% if strict_serde %}, deny_unknown_fields{% endif %})]
 pub struct {{ struct_name }} { {% if has_columns %}{% for column in columns %}
-    /// {{column.comment}}
-    {%if column.field_type == "String" -%}#[validate(length(max = {{column.max_length}}))]{%- endif%}
+    /// {% if column.comment %}{{ column.comment }}{% else %}{{ column.name }}{% endif %}{% if column.generation_expression %}
+    ///
+    /// 生成列，值由数据库计算，插入、更新时不写入，表达式：{{ column.generation_expression }}{% endif %}{% if column.example %}
+    ///
+    /// example: {{ column.example }}{% endif %}{% if column.lossy %}
+    ///
+    /// WARNING: {{ column.lossy }}{% endif %}
+    {%if column.field_type == "String" and column.max_length -%}#[validate(length(max = {{column.max_length}}))]{%- endif%}
+    {%if binary_encoding and column.field_type == "Vec<u8>" -%}#[serde(with = "super::binary::{{ binary_encoding }}{% if column.is_nullable %}_option{% endif %}")]{%- endif%}
+    {% if column.serde_aliases %}#[serde({% for alias in column.serde_aliases %}alias = "{{ alias }}"{% if not loop.last %}, {% endif %}{% endfor %})]{% endif %}
+    {% if column.rename %}#[sqlx(rename = "{{ column.rename }}")]{% endif %}
+    {% if column.attributes.serde %}#[serde({{ column.attributes.serde | join(sep=", ") }})]{% endif %}
+    {% if column.attributes.validate %}#[validate({{ column.attributes.validate | join(sep=", ") }})]{% endif %}
+    {% for attr in column.attributes.attrs %}#[{{ attr }}]
+    {% endfor %}{% if strict_serde and column.is_nullable %}{% if not binary_encoding or column.field_type != "Vec<u8>" %}#[serde(deserialize_with = "Option::deserialize")]{% endif %}{% endif %}
     pub {{column.name}}: {%if column.is_nullable %}Option<{{column.field_type}}>{% else %}{{column.field_type}}{% endif %},{% endfor %}{% endif %}
 }
 
@@ -175,188 +1293,497 @@ impl std::fmt::Display for {{ struct_name }} {
         write!(f, "{}", serde_json::json!(self))
     }
 }
+{% if wide %}
+// 列数较多，不使用派生宏
+impl<'r> FromRow<'r, {{ sqlx_row }}> for {{ struct_name }} {
+    fn from_row(row: &'r {{ sqlx_row }}) -> std::result::Result<Self, sqlx::Error> {
+        use sqlx::Row;
 
-impl {{ struct_name }} {
-    fn table_name() -> String {
-        "{{table.name}}".to_string()
+        Ok(Self { {% for column in columns %}
+            {{ column.name }}: row.try_get("{% if column.rename %}{{ column.rename }}{% else %}{{ column.name | replace(from="r#", to="") }}{% endif %}")?,{% endfor %}
+        })
+    }
+}
+{% endif %}
+{% if binary_encoding %}
+impl {{ struct_name }} { {% for column in columns %}{% if column.field_type == "Vec<u8>" %}{% set field = column.name | replace(from="r#", to="") %}
+    /// {% if column.comment %}{{ column.comment }}{% else %}{{ column.name }}{% endif %}，{{ binary_encoding }} 编码
+    pub fn {{ field }}_{{ binary_encoding }}(&self) -> {% if column.is_nullable %}Option<String>{% else %}String{% endif %} {
+        {% if column.is_nullable %}self.{{ column.name }}.as_deref().map(super::binary::{{ binary_encoding }}::encode){% else %}super::binary::{{ binary_encoding }}::encode(&self.{{ column.name }}){% endif %}
     }
 
-    fn columns() -> String {
-        "{{ column_names }}".to_string()
+    /// 从 {{ binary_encoding }} 编码的字符串设置{% if column.comment %}{{ column.comment }}{% else %}{{ column.name }}{% endif %}
+    pub fn set_{{ field }}_from_{{ binary_encoding }}(&mut self, s: &str) -> std::result::Result<(), String> {
+        self.{{ column.name }} = {% if column.is_nullable %}Some(super::binary::{{ binary_encoding }}::decode(s)?){% else %}super::binary::{{ binary_encoding }}::decode(s)?{% endif %};
+        Ok(())
     }
+{% endif %}{% endfor %}}
+{% endif %}
+{% if patch %}
+/// {% if table.comment %}{{ table.comment }}{% else %}{{ table.name }}{% endif %}的部分更新，没有出现的字段不修改，null 清空可为空的列
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all(serialize = "camelCase"){% if strict_serde %}, deny_unknown_fields{% endif %})]
+pub struct {{ struct_name }}Patch { {% for column in columns %}{% if column.is_primary_key %}{% continue %}{% endif %}
+    /// {% if column.comment %}{{ column.comment }}{% else %}{{ column.name }}{% endif %}
+    #[serde(default, skip_serializing_if = "super::Patch::is_absent"{% for alias in column.serde_aliases %}, alias = "{{ alias }}"{% endfor %})]
+    pub {{ column.name }}: super::Patch<{{ column.field_type }}>,{% endfor %}
+}
 
-    pub async fn fetch_by_id(id: u64) -> Result<Self> {
-        let sql = format!(
-            "select {} from {} where id = ?",
-            Self::columns(),
-            Self::table_name()
-        );
-        sqlx::query_as::<_, Self>(&sql)
-            .bind(id)
-            .fetch_one(DB.await)
-            .await
-            .map_err(|e| {
-                log::error!("{e}");
-                Error::SqlError
-            })
+{% set patch_columns = columns | filter(attribute="is_primary_key", value=false) %}
+impl {{ struct_name }}Patch {
+    /// 应用到模型，不可为空的列为 null 时返回错误，模型不做任何修改
+    pub fn apply(self, {% if not patch_columns %}_{% endif %}model: &mut {{ struct_name }}) -> std::result::Result<(), String> { {% for column in columns %}{% if column.is_primary_key or column.is_nullable %}{% continue %}{% endif %}
+        if let super::Patch::Null = self.{{ column.name }} {
+            return Err("{{ column.name | replace(from="r#", to="") }} 不能为 null".to_string());
+        }{% endfor %}{% for column in columns %}{% if column.is_primary_key %}{% continue %}{% endif %}
+        {% if column.is_nullable %}self.{{ column.name }}.apply(&mut model.{{ column.name }});{% else %}if let super::Patch::Value(value) = self.{{ column.name }} {
+            model.{{ column.name }} = value;
+        }{% endif %}{% endfor %}
+        Ok(())
     }
+}
+{% endif %}
+{% if try_from_row %}
+impl TryFrom<&{{ sqlx_row }}> for {{ struct_name }} {
+    type Error = sqlx::Error;
 
-    pub async fn fetch_all(req: &{{ struct_name }}Req) -> Result<Vec<Self>> {
-        let mut sql = format!("select {} from {}", Self::columns(), Self::table_name());
+    /// 逐列解码，失败时的错误信息包含表名和列名
+    fn try_from(row: &{{ sqlx_row }}) -> std::result::Result<Self, Self::Error> {
+        use sqlx::Row;
 
-        let mut where_sql = " WHERE 1=1 ".to_string();
+        Ok(Self { {% for column in columns %}{% if column.rename %}{% set raw = column.rename %}{% else %}{% set raw = column.name | replace(from="r#", to="") %}{% endif %}
+            {{ column.name }}: row
+                .try_get("{{ raw }}")
+                .map_err(|e| sqlx::Error::Decode(format!("failed decoding {{ table.name }}.{{ raw }}: {e}").into()))?,{% endfor %}
+        })
+    }
+}
+{% endif %}
+{% if any_row and any_fields %}
+#[cfg(feature = "any")]
+impl TryFrom<&sqlx::any::AnyRow> for {{ struct_name }} {
+    type Error = sqlx::Error;
 
-        {% if has_columns %}{% for column in columns %}
-        if let Some({{column.name}}) = &req.{{column.name}} {
-        {%if column.field_type == "String"%}
-            where_sql.push_str(&format!(" and {} like '%{}%' ",  "{{column.name}}", {{column.name}}));
-        {%else%}
-            where_sql.push_str(&format!(" and {} = {} ",  "{{column.name}}", {{column.name}}));
-        {%endif%}
+    /// 通过 sqlx::any 逐列解码，Any 不支持的类型先解码为整数或字符串再转换，失败时的错误信息包含表名和列名
+    fn try_from(row: &sqlx::any::AnyRow) -> std::result::Result<Self, Self::Error> {
+        use sqlx::Row;
+
+        fn decode_error(column: &str, e: impl std::fmt::Display) -> sqlx::Error {
+            sqlx::Error::Decode(format!("failed decoding {{ table.name }}.{column}: {e}").into())
+        }
+
+        Ok(Self { {% for f in any_fields %}
+            {{ f.field }}: {% if not f.via %}row
+                .try_get("{{ f.column }}")
+                .map_err(|e| decode_error("{{ f.column }}", e))?,{% elif f.nullable %}row
+                .try_get::<Option<{{ f.via }}>, _>("{{ f.column }}")
+                .map_err(|e| decode_error("{{ f.column }}", e))?
+                .map(|v| {{ f.convert }})
+                .transpose()
+                .map_err(|e| decode_error("{{ f.column }}", e))?,{% else %}{
+                let v: {{ f.via }} = row
+                    .try_get("{{ f.column }}")
+                    .map_err(|e| decode_error("{{ f.column }}", e))?;
+                {{ f.convert }}.map_err(|e| decode_error("{{ f.column }}", e))?
+            },{% endif %}{% endfor %}
+        })
+    }
+}
+{% endif %}
+{% if json_helpers %}
+impl {{ struct_name }} {
+    /// 从 JSON 对象构造，字段名同时支持 snake_case 和 camelCase，字符串形式的数字、布尔值会先转换
+    pub fn from_json_value(value: serde_json::Value) -> std::result::Result<Self, serde_json::Error> {
+        let serde_json::Value::Object(map) = value else {
+            return serde_json::from_value(value);
+        };
+        let mut fields = serde_json::Map::new();
+        for (key, value) in map {
+            match key.as_str() { {%- for field in json_fields %}
+                {{ field.keys | join(sep=" | ") }} => {
+                    fields.insert("{{ field.field }}".to_string(), {% if field.coerce %}super::json_coerce::{{ field.coerce }}(value){% else %}value{% endif %});
+                }{% endfor %}
+                _ => {
+                    fields.insert(key, value);
+                }
+            }
         }
-        {% endfor %}{% endif %}
+        serde_json::from_value(serde_json::Value::Object(fields))
+    }
+
+    /// 转换为 JSON 对象，字段名与序列化时一致（camelCase）
+    pub fn to_json_value(&self) -> serde_json::Value {
+        serde_json::json!(self)
+    }
+}
+{% endif %}
+{% if schema_check %}
+impl {{ struct_name }} {
+    /// 检查数据库中的表结构与生成代码时是否一致：列是否存在、类型能否解码为对应的字段，返回所有差异
+    pub async fn verify_schema(pool: &sqlx::Pool<{{ sqlx_database }}>) -> Vec<String> {
+        use sqlx::{Column, Executor, Statement, TypeInfo};
 
-        sql.push_str(&where_sql);
+        type Compatible = Option<fn(&<{{ sqlx_database }} as sqlx::Database>::TypeInfo) -> bool>;
+        // 枚举只检查列是否存在
+        let expected: [(&str, Compatible); {{ column_num }}] = [{% for column in columns %}
+            ("{% if column.rename %}{{ column.rename }}{% else %}{{ column.name | replace(from="r#", to="") }}{% endif %}", {% if column.enum_values %}None{% else %}Some(<{{ column.field_type }} as sqlx::Type<{{ sqlx_database }}>>::compatible){% endif %}),{% endfor %}
+        ];
 
-        sqlx::query_as::<_, Self>(&sql)
-            .fetch_all(DB.await)
+        let sql = format!("{} WHERE 1 = 0", Self::SELECT_ALL);
+        let statement = match pool.prepare(sql.as_str()).await {
+            Ok(statement) => statement,
+            Err(e) => return vec![format!("{}: {e}", Self::TABLE_NAME)],
+        };
+        let mut errors = vec![];
+        for (name, compatible) in expected {
+            match statement.columns().iter().find(|c| c.name() == name) {
+                None => errors.push(format!("{}.{name}: 列不存在", Self::TABLE_NAME)),
+                Some(column) if compatible.is_some_and(|f| !f(column.type_info())) => errors.push(format!(
+                    "{}.{name}: 类型 {} 与生成代码不一致",
+                    Self::TABLE_NAME,
+                    column.type_info().name()
+                )),
+                _ => {}
+            }
+        }
+        errors
+    }
+}
+{% endif %}
+impl {{ struct_name }} {
+    pub const TABLE_NAME: &str = "{{ table.name }}";
+    pub const COLUMNS: &str = "{{ sql.columns }}";
+    const SELECT_ALL: &str = "{{ sql.select_all }}";
+{% if id_type %}
+    const SELECT_BY_ID: &str = "{{ sql.select_by_id }}";
+    const INSERT: &str = "{{ sql.insert }}";
+    const UPDATE_BY_ID: &str = "{{ sql.update_by_id }}";
+    const DELETE_BY_ID: &str = "{{ sql.delete_by_id }}";
+{% endif %}    const COUNT: &str = "{{ sql.count }}";
+
+{% if id_type %}
+    {% if deprecated_attr %}{{ deprecated_attr }}
+    {% endif %}pub async fn fetch_by_id({{ pool_param }}id: {{ id_type }}) -> Result<{% if fetch_optional %}Option<Self>{% else %}Self{% endif %}> {
+{%- if metrics %}
+        let start = std::time::Instant::now();
+        let res: Result<{% if fetch_optional %}Option<Self>{% else %}Self{% endif %}> = async {
+{%- endif %}
+        sqlx::query_as::<_, Self>(Self::SELECT_BY_ID)
+            .bind(id)
+            .fetch_{% if fetch_optional %}optional{% else %}one{% endif %}({{ pool }})
             .await
-            .map_err(|e| {
-                log::error!("{e}");
-                Error::SqlError
-            })
+            .map_err(Error::from)
+{%- if metrics %}
+        }
+        .await;
+        super::metrics::record(Self::TABLE_NAME, "fetch_by_id", start, &res);
+        res
+{%- endif %}
+    }
+{% endif %}
+    {% if deprecated_attr %}{{ deprecated_attr }}
+    {% endif %}{% if not must_filter %}pub {% endif %}async fn fetch_all({{ pool_param }}req: &{{ struct_name }}Req) -> Result<Vec<Self>> {
+{%- if metrics %}
+        let start = std::time::Instant::now();
+        let res: Result<Vec<Self>> = async {
+{%- endif %}
+        let mut query = sqlx::QueryBuilder::<{{ sqlx_database }}>::new(Self::SELECT_ALL);
+        query.push(" WHERE 1=1 ");
+        Self::push_filters(req, &mut query);
+
+        query
+            .build_query_as::<Self>()
+            .fetch_all({{ pool }})
+            .await
+            .map_err(Error::from)
+{%- if metrics %}
+        }
+        .await;
+        super::metrics::record(Self::TABLE_NAME, "fetch_all", start, &res);
+        res
+{%- endif %}
     }
 
-    pub async fn insert(&mut self) -> Result<Self> {
-        let sql = format!(
-            "INSERT INTO {} ({}) VALUES({})",
-            Self::table_name(),
-            Self::columns(),
-            "{% for column in columns %}?,{% endfor %}".trim_end_matches(',')
-        );
-        let id = sqlx::query(&sql)
-            {% if has_columns %}{% for column in columns %}
-            .bind(&self.{{column.name}})
+{% if id_type %}
+    {% if deprecated_attr %}{{ deprecated_attr }}
+    {% endif %}pub async fn insert(&mut self{{ pool_tail }}) -> Result<Self> {
+{%- if metrics %}
+        let start = std::time::Instant::now();
+        let res: Result<Self> = async {
+{%- endif %}
+{%- for column in columns %}{% if column.name in client_uuid_columns %}
+        if self.{{ column.name }}{% if column.is_nullable %}.unwrap_or_default(){% endif %}.is_nil() {
+            self.{{ column.name }} = {% if column.is_nullable %}Some(uuid::Uuid::new_v4()){% else %}uuid::Uuid::new_v4(){% endif %};
+        }
+{%- endif %}{% endfor %}
+{% if wide %}
+        // 列数较多，按段绑定参数，避免过长的方法调用链
+        let query = {% if driver == 'postgres' %}sqlx::query_scalar{% else %}sqlx::query{% endif %}(Self::INSERT);
+        {% for chunk in column_chunks %}let query = query{% for column in chunk.columns %}{% if column.name in generated_columns or column.name in uuid_default_columns %}{% continue %}{% endif %}
+            .bind({% if column.name not in copy_columns %}&{% endif %}self.{{ column.name }}){% endfor %};
+        {% endfor %}
+{% if driver == 'postgres' %}
+        let id: {{ id_type }} = query
+            .fetch_one({{ pool }})
+            .await
+            .map_err(Error::from)?;
+{% else %}
+        let id = query
+            .execute({{ pool }})
+            .await
+            .map_err(Error::from)?
+            {% if driver == 'mysql' %}.last_insert_id(){% else %}.last_insert_rowid(){% endif %};
+{% endif %}
+{% elif driver == 'postgres' %}
+        let id: {{ id_type }} = sqlx::query_scalar(Self::INSERT)
+            {% if has_columns %}{% for column in columns %}{% if column.name in generated_columns or column.name in uuid_default_columns %}{% continue %}{% endif %}
+            .bind({% if column.name not in copy_columns %}&{% endif %}self.{{ column.name }})
             {% endfor %}{% endif %}
-            .execute(DB.await)
+            .fetch_one({{ pool }})
             .await
-            .map_err(|e| {
-                log::error!("{e}");
-                Error::SqlError
-            })?
-            .last_insert_id();
-        Self::fetch_by_id(id).await
-    }
-
-    pub async fn update(&mut self) -> Result<bool> {
-        let sql = format!(
-            "UPDATE {} set account = ?, set {} where id = ?",
-            Self::table_name(),
-            "{% for column in columns %}{{column.name}} = ?,{% endfor %}".trim_end_matches(',')
-        );
-        sqlx::query(&sql)
-            {% if has_columns %}{% for column in columns %}
-            .bind(&self.{{ column.name }})
+            .map_err(Error::from)?;
+{% else %}
+        let id = sqlx::query(Self::INSERT)
+            {% if has_columns %}{% for column in columns %}{% if column.name in generated_columns or column.name in uuid_default_columns %}{% continue %}{% endif %}
+            .bind({% if column.name not in copy_columns %}&{% endif %}self.{{ column.name }})
             {% endfor %}{% endif %}
-            .bind(&self.id)
-            .execute(DB.await)
+            .execute({{ pool }})
             .await
-            .map_err(|e| {
-                log::error!("{e}");
-                Error::SqlError
-            })
-            .map(|r| r.rows_affected() > 0)
+            .map_err(Error::from)?
+            {% if driver == 'mysql' %}.last_insert_id(){% else %}.last_insert_rowid(){% endif %};
+{% endif %}
+        Self::fetch_by_id({{ pool_arg }}{{ insert_id }}).await{% if fetch_optional %}?.ok_or(Error::NotFound){% endif %}
+{%- if metrics %}
+        }
+        .await;
+        super::metrics::record(Self::TABLE_NAME, "insert", start, &res);
+        res
+{%- endif %}
     }
 
-    pub async fn delete(&self) -> Result<bool> {
-        let sql = format!("DELETE FROM {} WHERE id = ?", Self::table_name());
-        sqlx::query(&sql)
-            .bind(self.id)
-            .execute(DB.await)
+    {% if deprecated_attr %}{{ deprecated_attr }}
+    {% endif %}pub async fn update(&mut self{{ pool_tail }}) -> Result<bool> {
+{%- if metrics %}
+        let start = std::time::Instant::now();
+        let res: Result<bool> = async {
+{%- endif %}
+{%- if wide %}
+        let query = sqlx::query(Self::UPDATE_BY_ID);
+        {% for chunk in column_chunks %}let query = query{% for column in chunk.columns %}{% if column.name in auto_updated_columns or column.name in generated_columns %}{% continue %}{% endif %}
+            .bind({% if column.name not in copy_columns %}&{% endif %}self.{{ column.name }}){% endfor %};
+        {% endfor %}
+        query{% else %}
+        sqlx::query(Self::UPDATE_BY_ID)
+            {% if has_columns %}{% for column in columns %}{% if column.name in auto_updated_columns or column.name in generated_columns %}{% continue %}{% endif %}
+            .bind({% if column.name not in copy_columns %}&{% endif %}self.{{ column.name }})
+            {% endfor %}{% endif %}{% endif %}
+            .bind({% if "id" not in copy_columns %}&{% endif %}self.id)
+            .execute({{ pool }})
             .await
-            .map_err(|e| {
-                log::error!("{e}");
-                Error::SqlError
-            })
+            .map_err(Error::from)
             .map(|r| r.rows_affected() > 0)
+{%- if metrics %}
+        }
+        .await;
+        super::metrics::record(Self::TABLE_NAME, "update", start, &res);
+        res
+{%- endif %}
     }
 
-    async fn count(where_sql: &str) -> Result<(i64,)> {
-        let count_sql = format!(
-            "SELECT count(*) FROM {} WHERE {}",
-            Self::table_name(),
-            where_sql
-        );
-
-        sqlx::query_as::<_, (i64,)>(&count_sql)
-            .fetch_one(DB.await)
+    {% if deprecated_attr %}{{ deprecated_attr }}
+    {% endif %}pub async fn delete(&self{{ pool_tail }}) -> Result<bool> {
+{%- if metrics %}
+        let start = std::time::Instant::now();
+        let res: Result<bool> = async {
+{%- endif %}
+        sqlx::query(Self::DELETE_BY_ID)
+            .bind({% if "id" not in copy_columns %}&{% endif %}self.id)
+            .execute({{ pool }})
             .await
-            .map_err(|e| {
-                log::error!("{e}");
-                Error::SqlError
-            })
+            .map_err(Error::from)
+            .map(|r| r.rows_affected() > 0)
+{%- if metrics %}
+        }
+        .await;
+        super::metrics::record(Self::TABLE_NAME, "delete", start, &res);
+        res
+{%- endif %}
     }
+{% endif %}
+    async fn count({{ pool_param }}req: &{{ struct_name }}Req) -> Result<(i64,)> {
+        let mut query = sqlx::QueryBuilder::<{{ sqlx_database }}>::new(Self::COUNT);
+        query.push(" WHERE 1 = 1 ");
+        Self::push_filters(req, &mut query);
 
-    pub async fn page(req: &{{ struct_name }}Req) -> Result<super::PageRes<Self>> {
-        let mut where_sql = " 1 = 1 ".to_string();
-        {% if has_columns %}{% for column in columns %}
-        if let Some({{column.name}}) = &req.{{column.name}} {
-            {%if column.field_type == "String"%}
-                where_sql.push_str(&format!(" and {} like '%{}%' ",  "{{column.name}}", {{column.name}}));
-            {%else%}
-                where_sql.push_str(&format!(" and {} = {} ",  "{{column.name}}", {{column.name}}));
-            {%endif%}
-        }
-        {% endfor %}{% endif %}
+        query
+            .build_query_as::<(i64,)>()
+            .fetch_one({{ pool }})
+            .await
+            .map_err(Error::from)
+    }
 
-        let (count,) = Self::count(&where_sql).await?;
+    {% if deprecated_attr %}{{ deprecated_attr }}
+    {% endif %}{% if not must_filter %}pub {% endif %}async fn page({{ pool_param }}req: &{{ struct_name }}Req) -> Result<super::PageRes<Self>> {
+{%- if metrics %}
+        let start = std::time::Instant::now();
+        let res: Result<super::PageRes<Self>> = async {
+{%- endif %}
+        let (count,) = Self::count({{ pool_arg }}req).await?;
         
-        let page_size = req.page_size.unwrap_or(20);
-        let mut page = req.page.unwrap_or(0) - 1;
-        if page < 0 {
-            page = 0;
-        }
-        where_sql.push_str(&format!(" LIMIT {}, {} ", page * page_size, page_size));
+        // 限制每页条数，避免一次查询过多的行
+        let page_size = req
+            .page_size
+            .unwrap_or(super::DEFAULT_PAGE_SIZE)
+            .clamp(1, super::MAX_PAGE_SIZE);
+        // 页码从 1 开始
+        let page = req.page.unwrap_or(1).max(1);
 
         let res = match count > 0 {
             true => {
-                let mut sql = format!(
-                    "SELECT {} FROM {} WHERE ",
-                    Self::columns(),
-                    Self::table_name()
-                );
-
-                sql.push_str(&where_sql);
-                sqlx::query_as::<_, Self>(&sql)
-                    .fetch_all(DB.await)
+                let mut query = sqlx::QueryBuilder::<{{ sqlx_database }}>::new(Self::SELECT_ALL);
+                query.push(" WHERE 1 = 1 ");
+                Self::push_filters(req, &mut query);
+                query
+                    .push(" LIMIT ")
+                    .push_bind(page_size)
+                    .push(" OFFSET ")
+                    .push_bind((page - 1) * page_size);
+
+                query
+                    .build_query_as::<Self>()
+                    .fetch_all({{ pool }})
                     .await
-                    .map_err(|e| {
-                        log::error!("{e}");
-                        Error::SqlError
-                    })?
+                    .map_err(Error::from)?
             }
             false => Vec::new(),
         };
         Ok(super::PageRes::new(count, page, page_size, &res))
+{%- if metrics %}
+        }
+        .await;
+        super::metrics::record(Self::TABLE_NAME, "page", start, &res);
+        res
+{%- endif %}
+    }
+{% if wide %}
+    /// 拼接 req 中的查询条件，列数较多，按段拆分为多个方法
+    fn push_filters<'a>(req: &'a {{ struct_name }}Req, query: &mut sqlx::QueryBuilder<'a, {{ sqlx_database }}>) { {% for chunk in column_chunks %}
+        Self::push_filters_{{ loop.index0 }}(req, query);{% endfor %}
+    }
+{% for chunk in column_chunks %}
+    fn push_filters_{{ loop.index0 }}<'a>(req: &'a {{ struct_name }}Req, query: &mut sqlx::QueryBuilder<'a, {{ sqlx_database }}>) {
+        {% for column in chunk.columns %}{% if column.field_type is not starting_with("Vec<") and column.field_type is not starting_with("sqlx_postgres") and column.name not in custom_type_columns %}
+        if let Some({{column.name}}) = &req.{{column.name}} {
+        {%if column.field_type == "String" or column.field_type == "CiString"%}
+            query.push(" and {{ filter_columns[column.name] }} like ").push_bind(format!("%{}%", {{column.name}}));
+        {%else%}
+            query.push(" and {{ filter_columns[column.name] }} = ").push_bind({{column.name}});
+        {%endif%}
+        }
+        {% endif %}{% endfor %}
+        {% for range in chunk.range_filters %}
+        if let Some({{ range.lower }}) = &req.{{ range.lower }} {
+            query.push(" and {{ range.column }} >= ").push_bind({{ range.lower }});
+        }
+        if let Some({{ range.upper }}) = &req.{{ range.upper }} {
+            query.push(" and {{ range.column }} <= ").push_bind({{ range.upper }});
+        }
+        {% endfor %}
+    }
+{% endfor %}{% else %}
+    /// 拼接 req 中的查询条件，条件的值都作为参数绑定
+    fn push_filters<'a>(req: &'a {{ struct_name }}Req, query: &mut sqlx::QueryBuilder<'a, {{ sqlx_database }}>) {
+        {% if has_columns %}{% for column in columns %}{% if column.field_type is not starting_with("Vec<") and column.field_type is not starting_with("sqlx_postgres") and column.name not in custom_type_columns %}
+        if let Some({{column.name}}) = &req.{{column.name}} {
+        {%if column.field_type == "String" or column.field_type == "CiString"%}
+            query.push(" and {{ filter_columns[column.name] }} like ").push_bind(format!("%{}%", {{column.name}}));
+        {%else%}
+            query.push(" and {{ filter_columns[column.name] }} = ").push_bind({{column.name}});
+        {%endif%}
+        }
+        {% endif %}{% endfor %}{% endif %}
+        {% for range in range_filters %}
+        if let Some({{ range.lower }}) = &req.{{ range.lower }} {
+            query.push(" and {{ range.column }} >= ").push_bind({{ range.lower }});
+        }
+        if let Some({{ range.upper }}) = &req.{{ range.upper }} {
+            query.push(" and {{ range.column }} <= ").push_bind({{ range.upper }});
+        }
+        {% endfor %}
+    }
+{% endif %}}
+
+{% if must_filter %}
+/// {% if table.comment %}{{ table.comment }}{% else %}{{ table.name }}{% endif %} 查询构造器，至少设置一个带索引的条件后才能查询列表
+pub struct {{ struct_name }}Query<S> {
+    req: {{ struct_name }}Req,
+    _state: std::marker::PhantomData<S>,
+}
+
+impl {{ struct_name }} {
+    pub fn query() -> {{ struct_name }}Query<super::Unfiltered> {
+        {{ struct_name }}Query {
+            req: Default::default(),
+            _state: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<S> {{ struct_name }}Query<S> { {% for column in columns %}
+    /// {% if column.comment %}{{ column.comment }}{% else %}{{ column.name }}{% endif %}{% if column.is_indexed %}（索引列）{% endif %}
+    pub fn {{ column.name }}(self, {{ column.name }}: {{ column.field_type }}) -> {{ struct_name }}Query<{% if column.is_indexed %}super::Filtered{% else %}S{% endif %}> {
+        let mut req = self.req;
+        req.{{ column.name }} = Some({{ column.name }});
+        {{ struct_name }}Query {
+            req,
+            _state: std::marker::PhantomData,
+        }
+    }
+{% endfor %}{% for range in range_filters %}{% for field in [range.lower, range.upper] %}
+    /// {{ range.comment }}{% if loop.first %}{% if range.is_time %}起{% else %}最小值{% endif %}{% else %}{% if range.is_time %}止{% else %}最大值{% endif %}{% endif %}（含）{% if range.is_indexed %}（索引列）{% endif %}
+    pub fn {{ field }}(self, {{ field }}: {{ range.field_type }}) -> {{ struct_name }}Query<{% if range.is_indexed %}super::Filtered{% else %}S{% endif %}> {
+        let mut req = self.req;
+        req.{{ field }} = Some({{ field }});
+        {{ struct_name }}Query {
+            req,
+            _state: std::marker::PhantomData,
+        }
+    }
+{% endfor %}{% endfor %}
+    pub fn with_page(mut self, page: i64, page_size: i64) -> Self {
+        self.req.page = Some(page);
+        self.req.page_size = Some(page_size);
+        self
     }
 }
 
+impl {{ struct_name }}Query<super::Filtered> {
+    pub async fn fetch_all(self{{ pool_tail }}) -> Result<Vec<{{ struct_name }}>> {
+        {{ struct_name }}::fetch_all({{ pool_arg }}&self.req).await
+    }
+
+    pub async fn page(self{{ pool_tail }}) -> Result<super::PageRes<{{ struct_name }}>> {
+        {{ struct_name }}::page({{ pool_arg }}&self.req).await
+    }
+}
+{% endif %}
 
-/// {{table.comment}}
+/// {% if table.comment %}{{ table.comment }}{% else %}{{ table.name }}{% endif %}
 #[derive(
     Debug,
     Default,
     Clone,
-    PartialEq,
-    Eq,
-    PartialOrd,
-    Ord,
-    Hash,
+    PartialEq,{% if derive_eq %}
+    Eq,{% endif %}{% if derive_partial_ord %}
+    PartialOrd,{% endif %}{% if derive_eq and derive_partial_ord %}
+    Ord,{% endif %}{% if derive_eq %}
+    Hash,{% endif %}
     Serialize,
-    Deserialize,
-    FromRow,
+    Deserialize,{% if not wide %}
+    FromRow,{% endif %}
     Validate,
-)]
+)]{% if strict_serde %}
+#[serde(deny_unknown_fields)]{% endif %}
 pub struct {{ struct_name }}Req { 
     pub time_type: Option<u8>,
     /// 开始时间
@@ -367,7 +1794,60 @@ pub struct {{ struct_name }}Req {
     pub page_size: Option<i64>,
 
     {% if has_columns %}{% for column in columns %}
-    /// {{column.comment}}
+    /// {% if column.comment %}{{ column.comment }}{% else %}{{ column.name }}{% endif %}
+    {%if binary_encoding and column.field_type == "Vec<u8>" -%}#[serde(with = "super::binary::{{ binary_encoding }}_option")]{%- endif%}
+    {% if column.serde_aliases %}#[serde({% for alias in column.serde_aliases %}alias = "{{ alias }}"{% if not loop.last %}, {% endif %}{% endfor %})]{% endif %}
     pub {{column.name}}: Option<{{column.field_type}}>,{% endfor %}{% endif %}
+    {% for range in range_filters %}
+    /// {{ range.comment }}{% if range.is_time %}起{% else %}最小值{% endif %}（含）
+    pub {{ range.lower }}: Option<{{ range.field_type }}>,
+    /// {{ range.comment }}{% if range.is_time %}止{% else %}最大值{% endif %}（含）
+    pub {{ range.upper }}: Option<{{ range.field_type }}>,{% endfor %}
 }
-"#;
+{% if db_struct %}
+/// {% if table.comment %}{{ table.comment }}{% else %}{{ table.name }}{% endif %}的数据访问，通过 db.{{ table.name }}() 获取{% if deprecated_attr %}
+{{ deprecated_attr }}{% endif %}
+#[derive(Debug, Clone, Copy)]
+pub struct {{ struct_name }}Repo<'a> {
+    pool: &'a sqlx::Pool<{{ sqlx_database }}>,
+}
+
+impl<'a> {{ struct_name }}Repo<'a> {
+    pub fn new(pool: &'a sqlx::Pool<{{ sqlx_database }}>) -> Self {
+        Self { pool }
+    }
+{% if id_type %}
+    pub async fn fetch_by_id(&self, id: {{ id_type }}) -> Result<{% if fetch_optional %}Option<{{ struct_name }}>{% else %}{{ struct_name }}{% endif %}> {
+        {{ struct_name }}::fetch_by_id(self.pool, id).await
+    }
+{% endif %}{% if must_filter %}
+    /// 至少设置一个带索引的条件后才能查询列表，见 {{ struct_name }}::query()
+    pub async fn fetch_all(&self, query: {{ struct_name }}Query<super::Filtered>) -> Result<Vec<{{ struct_name }}>> {
+        query.fetch_all(self.pool).await
+    }
+
+    pub async fn page(&self, query: {{ struct_name }}Query<super::Filtered>) -> Result<super::PageRes<{{ struct_name }}>> {
+        query.page(self.pool).await
+    }
+{% else %}
+    pub async fn fetch_all(&self, req: &{{ struct_name }}Req) -> Result<Vec<{{ struct_name }}>> {
+        {{ struct_name }}::fetch_all(self.pool, req).await
+    }
+
+    pub async fn page(&self, req: &{{ struct_name }}Req) -> Result<super::PageRes<{{ struct_name }}>> {
+        {{ struct_name }}::page(self.pool, req).await
+    }
+{% endif %}{% if id_type %}
+    pub async fn insert(&self, model: &mut {{ struct_name }}) -> Result<{{ struct_name }}> {
+        model.insert(self.pool).await
+    }
+
+    pub async fn update(&self, model: &mut {{ struct_name }}) -> Result<bool> {
+        model.update(self.pool).await
+    }
+
+    pub async fn delete(&self, model: &{{ struct_name }}) -> Result<bool> {
+        model.delete(self.pool).await
+    }
+{% endif %}}
+{% endif %}"#;
@@ -0,0 +1,84 @@
+//! 中文表名、列名转换为 ASCII 标识符
+//!
+//! 含有中文的表名无法作为模块名（文件名），`--transliterate pinyin` 将表名、列名中的汉字转换为不带声调的拼音，
+//! 每个字之间用 `_` 分隔，如 `用户信息` 转换为 `yong_hu_xin_xi`，SQL 中仍使用原来的名称。
+
+use clap::ValueEnum;
+use pinyin::ToPinyin;
+use serde::{Deserialize, Serialize};
+
+/// 名称的转换方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Transliterate {
+    /// 汉字转换为拼音
+    Pinyin,
+}
+
+impl Transliterate {
+    /// 名称中含有非 ASCII 字符时转换，否则返回 None
+    pub fn convert(&self, name: &str) -> Option<String> {
+        if name.is_ascii() {
+            return None;
+        }
+        match self {
+            Self::Pinyin => Some(pinyin(name)),
+        }
+    }
+}
+
+/// 汉字转换为拼音，ASCII 字母和数字保留，其他字符作为分隔符
+fn pinyin(name: &str) -> String {
+    let mut words = vec![];
+    let mut word = String::new();
+    for (c, py) in name.chars().zip(name.to_pinyin()) {
+        if c.is_ascii_alphanumeric() {
+            word.push(c.to_ascii_lowercase());
+            continue;
+        }
+        if !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+        if let Some(py) = py {
+            // 不带声调的拼音中仍有 ü，按输入法的习惯写为 v
+            words.push(py.plain().replace('ü', "v"));
+        }
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    let name = words.join("_");
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{name}")
+    } else {
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_chinese_to_pinyin() {
+        let pinyin = Transliterate::Pinyin;
+        assert_eq!(
+            pinyin.convert("用户信息").as_deref(),
+            Some("yong_hu_xin_xi")
+        );
+        assert_eq!(pinyin.convert("绿色").as_deref(), Some("lv_se"));
+        assert_eq!(pinyin.convert("用户ID 2").as_deref(), Some("yong_hu_id_2"));
+    }
+
+    #[test]
+    fn keeps_ascii_names() {
+        assert_eq!(Transliterate::Pinyin.convert("user_info"), None);
+    }
+
+    #[test]
+    fn prefixes_invalid_identifiers() {
+        let pinyin = Transliterate::Pinyin;
+        assert_eq!(pinyin.convert("2号表").as_deref(), Some("_2_hao_biao"));
+        assert_eq!(pinyin.convert("（）").as_deref(), Some("_"));
+    }
+}
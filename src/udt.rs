@@ -0,0 +1,88 @@
+//! 自定义类型映射
+//!
+//! 公司内部的域、复合类型等（PostgreSQL 的 CREATE DOMAIN、CREATE TYPE）默认按基础类型映射或映射为 String，
+//! 可以在配置文件中映射为自己的 Rust 类型，键为数据库中的类型名：
+//!
+//! ```toml
+//! # 已有的类型，需要自行实现 sqlx::Type，如 #[sqlx(type_name = "money_cents")]
+//! money_cents = { rust = "billing::MoneyCents" }
+//! # 生成包装 inner 的 newtype（udt.rs），带有 #[sqlx(type_name = "email_address")]，
+//! # Display、FromStr 转发给 inner
+//! email_address = { rust = "EmailAddress", inner = "String" }
+//! ```
+//!
+//! 自定义类型不一定实现了 Eq、Ord、Hash、Default 和 Display，使用了自定义类型的结构体不派生这些 trait，
+//! 列表查询也不使用这些列作为条件。
+
+use std::{collections::HashMap, fs};
+
+use serde::{Deserialize, Serialize};
+
+/// 单个类型的映射
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct TypeMapping {
+    /// Rust 类型的路径，生成 newtype 时为类型名
+    pub rust: String,
+    /// 生成 newtype 时包装的类型，为空时使用已有的类型
+    pub inner: Option<String>,
+}
+
+/// 类型映射配置，K：数据库中的类型名
+#[derive(Deserialize, Default, Debug, Clone)]
+#[serde(transparent)]
+pub struct TypeMap(pub HashMap<String, TypeMapping>);
+
+/// 生成的 newtype
+#[derive(Serialize, Debug, Clone)]
+pub struct NewType {
+    pub name: String,
+    /// 数据库中的类型名
+    pub type_name: String,
+    pub inner: String,
+}
+
+impl TypeMap {
+    /// 读取类型映射配置文件，作为 clap 的 value_parser 使用
+    pub fn load(path: &str) -> Result<Self, String> {
+        let contents = fs::read_to_string(path).map_err(|e| format!("读取 {path} 失败：{e}"))?;
+        toml::from_str(&contents).map_err(|e| format!("解析 {path} 失败：{e}"))
+    }
+
+    /// 数据库类型对应的 Rust 类型，生成的 newtype 带有 super:: 前缀
+    pub fn rust_type(&self, column_type: &str) -> Option<String> {
+        let mapping = self
+            .0
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(column_type))
+            .map(|(_, mapping)| mapping)?;
+        Some(match mapping.inner {
+            Some(_) => format!("super::{}", mapping.rust),
+            None => mapping.rust.clone(),
+        })
+    }
+
+    /// Rust 类型是否来自类型映射
+    pub fn is_custom(&self, field_type: &str) -> bool {
+        self.0.values().any(|m| {
+            field_type == m.rust || field_type.strip_prefix("super::") == Some(m.rust.as_str())
+        })
+    }
+
+    /// 需要生成的 newtype，按类型名排序
+    pub fn new_types(&self) -> Vec<NewType> {
+        let mut types = self
+            .0
+            .iter()
+            .filter_map(|(type_name, m)| {
+                Some(NewType {
+                    name: m.rust.clone(),
+                    type_name: type_name.clone(),
+                    inner: m.inner.clone()?,
+                })
+            })
+            .collect::<Vec<_>>();
+        types.sort_by(|a, b| a.type_name.cmp(&b.type_name));
+        types
+    }
+}
@@ -0,0 +1,140 @@
+//! 数据库服务的版本
+//!
+//! 旧版本的服务缺少部分会话变量和系统表的列，连接后先读取版本，再调整会话设置和读取表结构的语句：
+//!
+//! - MySQL 5.7.8 之前没有 `max_execution_time`，MariaDB 使用 `max_statement_time`（10.1 开始支持）
+//! - MySQL 5.6.5 之前不支持 `SET SESSION TRANSACTION READ ONLY`
+//! - MySQL 5.7.6、MariaDB 10.2.5 之前 `information_schema.COLUMNS` 没有 `GENERATION_EXPRESSION`
+//! - PostgreSQL 9.3 之前没有 `lock_timeout`，10 之前没有分区表（`relispartition`、`pg_get_partkeydef`）
+
+use std::fmt::Display;
+
+use sqlx::Connection;
+
+use crate::Driver;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ServerVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+    /// 是否为 MariaDB
+    pub mariadb: bool,
+}
+
+impl ServerVersion {
+    /// 连接数据库读取版本，Sqlite 不需要兼容，返回默认值
+    pub async fn detect(driver: Driver, url: &str) -> anyhow::Result<Self> {
+        let version = match driver {
+            Driver::Mysql => {
+                let mut conn = sqlx::MySqlConnection::connect(url).await?;
+                let version = sqlx::query_scalar::<_, String>("SELECT VERSION()")
+                    .fetch_one(&mut conn)
+                    .await?;
+                Self::parse_mysql(&version)
+            }
+            Driver::Postgres => {
+                let mut conn = sqlx::PgConnection::connect(url).await?;
+                let version = sqlx::query_scalar::<_, String>("SHOW server_version_num")
+                    .fetch_one(&mut conn)
+                    .await?;
+                Self::parse_postgres(version.trim().parse()?)
+            }
+            Driver::Sqlite => Self::default(),
+        };
+        Ok(version)
+    }
+
+    /// 解析 MySQL 的 VERSION()，如：5.6.51-log、10.6.12-MariaDB、5.5.5-10.3.39-MariaDB
+    pub fn parse_mysql(version: &str) -> Self {
+        let mariadb = version.to_lowercase().contains("mariadb");
+        // 旧的 MariaDB 为了兼容复制协议，会在版本号前加上 5.5.5-
+        let version = match mariadb {
+            true => version.strip_prefix("5.5.5-").unwrap_or(version),
+            false => version,
+        };
+        let mut numbers = version
+            .split(|c: char| !c.is_ascii_digit())
+            .take(3)
+            .map(|n| n.parse().unwrap_or_default());
+        Self {
+            major: numbers.next().unwrap_or_default(),
+            minor: numbers.next().unwrap_or_default(),
+            patch: numbers.next().unwrap_or_default(),
+            mariadb,
+        }
+    }
+
+    /// 解析 PostgreSQL 的 server_version_num，如：90624（9.6.24）、150004（15.4）
+    pub fn parse_postgres(num: u32) -> Self {
+        match num >= 100000 {
+            true => Self {
+                major: num / 10000,
+                minor: num % 10000,
+                ..Default::default()
+            },
+            false => Self {
+                major: num / 10000,
+                minor: num / 100 % 100,
+                patch: num % 100,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// 版本是否不低于 major.minor.patch
+    pub fn at_least(&self, major: u32, minor: u32, patch: u32) -> bool {
+        (self.major, self.minor, self.patch) >= (major, minor, patch)
+    }
+}
+
+impl Display for ServerVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if self.mariadb {
+            write!(f, "-MariaDB")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_mysql_version() {
+        let version = ServerVersion::parse_mysql("5.6.51-log");
+        assert_eq!((version.major, version.minor, version.patch), (5, 6, 51));
+        assert!(!version.mariadb);
+        assert_eq!(ServerVersion::parse_mysql("8.0.36").to_string(), "8.0.36");
+    }
+
+    #[test]
+    fn parses_mariadb_version() {
+        let version = ServerVersion::parse_mysql("10.6.12-MariaDB");
+        assert_eq!((version.major, version.minor, version.patch), (10, 6, 12));
+        assert!(version.mariadb);
+        // 兼容复制协议的 5.5.5- 前缀
+        let version = ServerVersion::parse_mysql("5.5.5-10.3.39-MariaDB-1:10.3.39+maria~ubu2004");
+        assert_eq!(version.to_string(), "10.3.39-MariaDB");
+    }
+
+    #[test]
+    fn parses_postgres_version() {
+        assert_eq!(ServerVersion::parse_postgres(90624).to_string(), "9.6.24");
+        assert_eq!(ServerVersion::parse_postgres(150004).to_string(), "15.4.0");
+        assert_eq!(ServerVersion::parse_postgres(100000).to_string(), "10.0.0");
+    }
+
+    #[test]
+    fn compares_versions() {
+        let version = ServerVersion::parse_mysql("5.7.8");
+        assert!(version.at_least(5, 7, 8));
+        assert!(version.at_least(5, 6, 5));
+        assert!(!version.at_least(5, 7, 9));
+        assert!(!version.at_least(8, 0, 0));
+        assert!(ServerVersion::parse_postgres(90300).at_least(9, 3, 0));
+        assert!(!ServerVersion::parse_postgres(90224).at_least(9, 3, 0));
+    }
+}
@@ -0,0 +1,32 @@
+//! 宽表（列数很多的表，如遥测、埋点表）的生成
+//!
+//! 上千个字段的结构体使用派生宏和很长的 `.bind()` 链时，编译会超过宏展开和表达式嵌套的限制。
+//! 列数超过 `--wide-table-columns` 的表按分段生成：
+//! - 手动实现 `sqlx::FromRow`，不派生 `FromRow`、`PartialOrd`、`Ord`
+//! - 插入、更新按段绑定参数，每段是一条单独的语句
+//! - 查询条件按段拆分为多个方法
+
+use serde::Serialize;
+
+use crate::{sql::RangeFilter, Column};
+
+/// 每段的列数
+pub const CHUNK_SIZE: usize = 100;
+
+/// 一段列及其范围查询字段
+#[derive(Serialize, Debug, Clone)]
+pub struct Chunk<'a> {
+    pub columns: Vec<&'a Column>,
+    pub range_filters: Vec<RangeFilter>,
+}
+
+/// 按表定义中的顺序分段
+pub fn chunks<'a>(columns: &[&'a Column]) -> Vec<Chunk<'a>> {
+    columns
+        .chunks(CHUNK_SIZE)
+        .map(|chunk| Chunk {
+            columns: chunk.to_vec(),
+            range_filters: chunk.iter().filter_map(|c| RangeFilter::new(c)).collect(),
+        })
+        .collect()
+}
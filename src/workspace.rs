@@ -0,0 +1,78 @@
+//! 按表分组输出到工作区中的多个 crate
+//!
+//! `--crate-target 目录=表名1,billing_*` 将匹配的表生成到指定目录，每个目录都有自己的 mod.rs、error.rs、result.rs，
+//! 未匹配任何目录的表仍然生成到默认路径。目录形如 `crates/billing-models/src/generated` 且 crate 中还没有
+//! Cargo.toml、src/lib.rs 时，会一并生成引用该目录的 Cargo.toml 和 src/lib.rs，已有的文件不会修改。
+
+use std::{path::Path, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{glob_matches, parse_group, requirements::Requirements, self_test};
+
+/// 输出到指定目录的一组表
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrateTarget {
+    /// 输出目录，以 / 结尾
+    pub path: String,
+    /// 表名，支持 * 通配符
+    pub tables: Vec<String>,
+}
+
+impl FromStr for CrateTarget {
+    type Err = String;
+
+    /// crates/billing-models/src/generated=billing_*,invoice
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (mut path, tables) = parse_group(s, "目录")?;
+        if !path.ends_with('/') {
+            path.push('/');
+        }
+        Ok(Self { path, tables })
+    }
+}
+
+impl CrateTarget {
+    /// 表名是否属于该目录
+    pub fn matches(&self, table_name: &str) -> bool {
+        self.tables
+            .iter()
+            .any(|pattern| glob_matches(pattern, table_name))
+    }
+
+    /// crate 中还没有的 Cargo.toml、src/lib.rs，K：文件路径，V：文件内容；
+    /// 目录不是 crate 的 src 下的一级目录时返回空
+    pub fn scaffold(&self, requirements: &Requirements) -> Vec<(String, String)> {
+        let dir = Path::new(self.path.trim_end_matches('/'));
+        let (Some(module), Some(src)) = (dir.file_name(), dir.parent()) else {
+            return vec![];
+        };
+        let Some(root) = src
+            .parent()
+            .filter(|_| src.file_name() == Some("src".as_ref()))
+        else {
+            return vec![];
+        };
+        let name = match root.file_name() {
+            Some(name) => name.to_string_lossy().to_string(),
+            None => return vec![],
+        };
+
+        let mut files = vec![];
+        let manifest = root.join("Cargo.toml");
+        if !manifest.exists() {
+            files.push((
+                manifest.display().to_string(),
+                self_test::manifest(&name, requirements, false),
+            ));
+        }
+        let lib = src.join("lib.rs");
+        if !lib.exists() {
+            files.push((
+                lib.display().to_string(),
+                self_test::lib_rs(&module.to_string_lossy()),
+            ));
+        }
+        files
+    }
+}
@@ -0,0 +1,43 @@
+//! 集成测试共用的 Sqlite 数据库和渲染方法
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+
+use clap::Parser;
+use sqlx::{Connection, Executor, SqliteConnection};
+use sqlx_db_cli::{Column, Generator, RenderOptions, Table};
+
+/// 测试使用的临时目录，按名称和进程号区分
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("sqlx-db-cli-{name}-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// 在 dir 中创建 Sqlite 数据库 {name}.sqlite 并执行建表语句，返回连接地址
+pub async fn sqlite(dir: &Path, name: &str, statements: &[&str]) -> String {
+    let url = format!(
+        "sqlite://{}?mode=rwc",
+        dir.join(format!("{name}.sqlite")).display()
+    );
+    let mut conn = SqliteConnection::connect(&url).await.unwrap();
+    for statement in statements {
+        conn.execute(*statement).await.unwrap();
+    }
+    conn.close().await.unwrap();
+    url
+}
+
+/// 读取单张表和它的列，列未经过类型映射
+pub async fn table(url: &str, table: &str) -> (Table, Vec<Column>) {
+    let generator = Generator::try_parse_from(["sqlx-db-cli", "-D", url, "-t", table]).unwrap();
+    let (mut tables, columns) = generator.prepare().await.unwrap();
+    (tables.remove(0), columns)
+}
+
+/// 按选项渲染单张表的代码
+pub async fn render(url: &str, name: &str, options: &RenderOptions) -> String {
+    let (table, mut columns) = table(url, name).await;
+    options.map_types(&mut columns).unwrap();
+    options.render_table(&table, &columns).unwrap()
+}
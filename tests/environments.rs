@@ -0,0 +1,74 @@
+//! 按环境从表结构快照分别生成，并输出环境间的差异
+
+mod common;
+
+use clap::Parser;
+use sqlx_db_cli::Generator;
+
+/// 创建 Sqlite 数据库并保存表结构快照，返回数据库地址和快照文件
+async fn save_snapshot(dir: &std::path::Path, name: &str, ddl: &str) -> (String, String) {
+    let url = common::sqlite(dir, name, &[ddl]).await;
+
+    let snapshot = format!("{}/{name}.json", dir.display());
+    let out = format!("{}/{name}/", dir.display());
+    let mut generator = Generator::try_parse_from([
+        "sqlx-db-cli",
+        "-D",
+        &url,
+        "-t",
+        "user",
+        "--save-snapshot",
+        &snapshot,
+        &out,
+    ])
+    .unwrap();
+    generator.run().await.unwrap();
+    (url, snapshot)
+}
+
+#[tokio::test]
+async fn generates_each_environment_and_reports_differences() {
+    let dir = common::temp_dir("environments");
+    let (url, prod) = save_snapshot(
+        &dir,
+        "prod",
+        "CREATE TABLE user (id INTEGER PRIMARY KEY, name TEXT NOT NULL)",
+    )
+    .await;
+    let (_, staging) = save_snapshot(
+        &dir,
+        "staging",
+        "CREATE TABLE user (id INTEGER PRIMARY KEY, name TEXT, age INTEGER)",
+    )
+    .await;
+
+    let out = format!("{}/models/", dir.display());
+    let mut generator = Generator::try_parse_from([
+        "sqlx-db-cli",
+        "-D",
+        &url,
+        "--environment",
+        &format!("prod={prod}"),
+        "--environment",
+        &format!("staging={staging}"),
+        &out,
+    ])
+    .unwrap();
+    generator.run().await.unwrap();
+
+    let read = |path: &str| std::fs::read_to_string(format!("{out}{path}")).unwrap();
+    assert_eq!(
+        read("mod.rs"),
+        "//! 由 sqlx-db-cli 按环境生成，差异见 ENVIRONMENTS.md\n\npub mod prod;\npub mod staging;\n"
+    );
+    assert!(read("prod/user.rs").contains("pub name: String,"));
+    assert!(read("staging/user.rs").contains("pub age: Option<i32>,"));
+    let report = read("ENVIRONMENTS.md");
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(
+        report.contains(
+            "- `user`\n  - 新增 `age: Option<i32>`\n  - `name`：`String` → `Option<String>`\n"
+        ),
+        "{report}"
+    );
+}
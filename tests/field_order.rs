@@ -0,0 +1,33 @@
+//! 生成的字段顺序与表定义中列的顺序一致
+
+mod common;
+
+use sqlx_db_cli::{Driver, RenderOptions};
+
+#[tokio::test]
+async fn fields_follow_ordinal_position() {
+    let dir = common::temp_dir("field-order");
+    let url = common::sqlite(
+        &dir,
+        "db",
+        &["CREATE TABLE t (id INTEGER PRIMARY KEY, zeta TEXT, alpha TEXT, mid INTEGER)"],
+    )
+    .await;
+
+    let (table, mut columns) = common::table(&url, "t").await;
+    let options = RenderOptions::new(Driver::Sqlite);
+    options.map_types(&mut columns).unwrap();
+    // 打乱读取到的顺序，生成时仍按表定义排列
+    columns.reverse();
+    let contents = options.render_table(&table, &columns).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let positions = ["id", "zeta", "alpha", "mid"]
+        .iter()
+        .map(|name| contents.find(&format!("pub {name}:")).unwrap())
+        .collect::<Vec<_>>();
+    assert!(
+        positions.windows(2).all(|w| w[0] < w[1]),
+        "字段顺序与表定义不一致：\n{contents}"
+    );
+}
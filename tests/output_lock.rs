@@ -0,0 +1,107 @@
+//! 生成结束后删除输出目录的锁文件
+
+mod common;
+
+use clap::Parser;
+use sqlx_db_cli::Generator;
+
+#[tokio::test]
+async fn lock_file_is_removed() {
+    let root = common::temp_dir("output-lock");
+    let url = common::sqlite(
+        &root,
+        "db",
+        &["CREATE TABLE t (id INTEGER PRIMARY KEY, name TEXT)"],
+    )
+    .await;
+
+    let out = root.join("models");
+    let mut generator =
+        Generator::try_parse_from(["sqlx-db-cli", "-D", &url, &out.display().to_string(), "-q"])
+            .unwrap();
+    generator.run().await.unwrap();
+
+    let entries = std::fs::read_dir(&root)
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+        .collect::<Vec<_>>();
+    std::fs::remove_dir_all(&root).unwrap();
+    assert!(entries.contains(&"models".to_string()), "{entries:?}");
+    assert!(
+        !entries.iter().any(|e| e.ends_with(".lock")),
+        "锁文件没有删除：{entries:?}"
+    );
+}
+
+#[tokio::test]
+async fn dry_run_reports_stale_staging_dirs() {
+    let root = common::temp_dir("stale");
+    let url = common::sqlite(
+        &root,
+        "db",
+        &["CREATE TABLE t (id INTEGER PRIMARY KEY, name TEXT)"],
+    )
+    .await;
+
+    let out = root.join("models");
+    let out = out.display().to_string();
+    let mut generator = Generator::try_parse_from(["sqlx-db-cli", "-D", &url, &out, "-q"]).unwrap();
+    generator.run().await.unwrap();
+    // 之前中断留下的临时目录
+    let stale = root.join(".models.staging.1");
+    std::fs::create_dir_all(&stale).unwrap();
+
+    let mut generator =
+        Generator::try_parse_from(["sqlx-db-cli", "-D", &url, &out, "-q", "--dry-run"]).unwrap();
+    let changes = generator.run().await.unwrap();
+    assert_eq!(changes.removed, vec![stale.display().to_string()]);
+    assert!(changes.added.is_empty() && changes.modified.is_empty());
+    assert!(!changes.is_empty());
+    assert!(stale.exists());
+
+    let mut generator = Generator::try_parse_from(["sqlx-db-cli", "-D", &url, &out, "-q"]).unwrap();
+    generator.run().await.unwrap();
+    let exists = stale.exists();
+    std::fs::remove_dir_all(&root).unwrap();
+    assert!(!exists);
+}
+
+#[tokio::test]
+async fn restores_dir_moved_away_by_interrupted_run() {
+    let root = common::temp_dir("interrupted");
+    let url = common::sqlite(
+        &root,
+        "db",
+        &["CREATE TABLE t (id INTEGER PRIMARY KEY, name TEXT)"],
+    )
+    .await;
+
+    let out = root.join("models");
+    let out_arg = out.display().to_string();
+    let mut generator =
+        Generator::try_parse_from(["sqlx-db-cli", "-D", &url, &out_arg, "-q"]).unwrap();
+    generator.run().await.unwrap();
+    std::fs::write(out.join("custom.txt"), "kept").unwrap();
+    // 两次重命名之间中断：输出目录已移走，临时目录还没有移入
+    let old = root.join(".models.old.1");
+    std::fs::rename(&out, &old).unwrap();
+    std::fs::create_dir_all(root.join(".models.staging.1")).unwrap();
+
+    let mut generator =
+        Generator::try_parse_from(["sqlx-db-cli", "-D", &url, &out_arg, "-q", "--dry-run"])
+            .unwrap();
+    let changes = generator.run().await.unwrap();
+    assert_eq!(
+        changes.removed,
+        vec![root.join(".models.staging.1").display().to_string()]
+    );
+
+    let mut generator =
+        Generator::try_parse_from(["sqlx-db-cli", "-D", &url, &out_arg, "-q"]).unwrap();
+    generator.run().await.unwrap();
+    let custom = std::fs::read_to_string(out.join("custom.txt"));
+    let old_exists = old.exists();
+    std::fs::remove_dir_all(&root).unwrap();
+    assert_eq!(custom.unwrap(), "kept");
+    assert!(!old_exists);
+}
@@ -0,0 +1,30 @@
+//! 时间、数值列的范围条件作为参数绑定，不拼接到 SQL 中
+
+mod common;
+
+use sqlx_db_cli::{Driver, RenderOptions};
+
+#[tokio::test]
+async fn range_filters_are_bound() {
+    let dir = common::temp_dir("range-filter");
+    let url = common::sqlite(
+        &dir,
+        "db",
+        &["CREATE TABLE t (id INTEGER PRIMARY KEY, created_at DATETIME, amount INTEGER)"],
+    )
+    .await;
+
+    let contents = common::render(&url, "t", &RenderOptions::new(Driver::Sqlite)).await;
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    for bound in [
+        r#"query.push(" and created_at >= ").push_bind(created_at_from);"#,
+        r#"query.push(" and created_at <= ").push_bind(created_at_to);"#,
+        r#"query.push(" and amount >= ").push_bind(amount_min);"#,
+        r#"query.push(" and amount <= ").push_bind(amount_max);"#,
+    ] {
+        assert!(contents.contains(bound), "缺少 {bound}：\n{contents}");
+    }
+    assert!(!contents.contains(">= '{}'"), "{contents}");
+    assert!(!contents.contains("where_sql"), "{contents}");
+}
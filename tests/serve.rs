@@ -0,0 +1,122 @@
+//! serve 子命令只生成到临时目录，不写入命令行中指定的输出
+#![cfg(feature = "serve")]
+
+mod common;
+
+use std::{net::TcpListener, path::PathBuf, time::Duration};
+
+use clap::Parser;
+use sqlx_db_cli::Generator;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+
+/// 创建带两张表的 Sqlite 数据库，其中一张表名含空格，返回临时目录和连接地址
+async fn database(name: &str) -> (PathBuf, String) {
+    let dir = common::temp_dir(&format!("serve-{name}"));
+    let url = common::sqlite(
+        &dir,
+        "db",
+        &[
+            "CREATE TABLE t (id INTEGER PRIMARY KEY, name TEXT)",
+            "CREATE TABLE \"order item\" (id INTEGER PRIMARY KEY, qty INTEGER)",
+        ],
+    )
+    .await;
+    (dir, url)
+}
+
+/// 在空闲端口上启动服务，返回监听的地址
+fn serve(args: &[&str]) -> String {
+    let addr = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .to_string();
+    let mut generator = Generator::try_parse_from(args).unwrap();
+    let listen = addr.clone();
+    tokio::spawn(async move { generator.serve(&listen).await.unwrap() });
+    addr
+}
+
+/// 发送 GET 请求，返回状态行和响应体，服务未就绪时重试
+async fn get(addr: &str, path: &str) -> (String, Vec<u8>) {
+    let mut stream = None;
+    for _ in 0..50 {
+        match TcpStream::connect(addr).await {
+            Ok(s) => {
+                stream = Some(s);
+                break;
+            }
+            Err(_) => tokio::time::sleep(Duration::from_millis(100)).await,
+        }
+    }
+    let mut stream = stream.expect("服务未启动");
+    stream
+        .write_all(format!("GET {path} HTTP/1.1\r\nHost: {addr}\r\n\r\n").as_bytes())
+        .await
+        .unwrap();
+    let mut response = vec![];
+    stream.read_to_end(&mut response).await.unwrap();
+    let split = response.windows(4).position(|w| w == b"\r\n\r\n").unwrap();
+    let head = String::from_utf8_lossy(&response[..split]).to_string();
+    let status = head.lines().next().unwrap_or_default().to_string();
+    (status, response[split + 4..].to_vec())
+}
+
+#[tokio::test]
+async fn serve_writes_nothing_outside_temp_dir() {
+    let (dir, url) = database("output").await;
+    let out = std::env::temp_dir().join(format!("sqlx-db-cli-serve-out-{}", std::process::id()));
+    let out = format!("{}/", out.display());
+    let addr = serve(&["sqlx-db-cli", "-D", &url, &out, "--requirements"]);
+
+    let (status, body) = get(&addr, "/").await;
+    assert_eq!(status, "HTTP/1.1 200 OK");
+    let index = String::from_utf8(body).unwrap();
+    assert!(index.contains("href=\"/tables/order%20item\""), "{index}");
+
+    let (status, body) = get(&addr, "/tables/order%20item").await;
+    assert_eq!(status, "HTTP/1.1 200 OK");
+    assert!(String::from_utf8(body)
+        .unwrap()
+        .contains("pub struct OrderItem"));
+
+    let (status, _) = get(&addr, "/bundle.zip").await;
+    assert_eq!(status, "HTTP/1.1 200 OK");
+
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(!PathBuf::from(&out).exists(), "不应写入输出目录 {out}");
+}
+
+#[tokio::test]
+async fn serve_ignores_stdout() {
+    let (dir, url) = database("stdout").await;
+    let addr = serve(&["sqlx-db-cli", "-D", &url, "--stdout"]);
+
+    // 预览不受 --stdout 影响
+    let (status, body) = get(&addr, "/tables/t").await;
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(
+        status,
+        "HTTP/1.1 200 OK",
+        "{}",
+        String::from_utf8_lossy(&body)
+    );
+}
+
+#[tokio::test]
+async fn serve_hides_error_details() {
+    let file = std::env::temp_dir().join(format!(
+        "sqlx-db-cli-serve-missing-{}/db.sqlite",
+        std::process::id()
+    ));
+    let url = format!("sqlite://{}", file.display());
+    let addr = serve(&["sqlx-db-cli", "-D", &url]);
+
+    let (status, body) = get(&addr, "/tables").await;
+    assert_eq!(status, "HTTP/1.1 500 Internal Server Error");
+    let body = String::from_utf8(body).unwrap();
+    assert!(!body.contains("sqlx-db-cli-serve-missing"), "{body}");
+}
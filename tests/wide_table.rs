@@ -0,0 +1,28 @@
+//! 列数超过 --wide-table-columns 的表按宽表生成
+
+mod common;
+
+use sqlx_db_cli::{Driver, RenderOptions};
+
+#[tokio::test]
+async fn wide_table_is_chunked() {
+    let dir = common::temp_dir("wide-table");
+    let url = common::sqlite(
+        &dir,
+        "db",
+        &["CREATE TABLE t (id INTEGER PRIMARY KEY, a TEXT, b INTEGER, c REAL, d TEXT)"],
+    )
+    .await;
+
+    let options = RenderOptions::new(Driver::Sqlite).with_wide_table_columns(3);
+    let contents = common::render(&url, "t", &options).await;
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(
+        contents.contains("impl<'r> FromRow<'r, sqlx::sqlite::SqliteRow> for T"),
+        "{contents}"
+    );
+    assert!(!contents.contains("    FromRow,\n"), "{contents}");
+    assert!(contents.contains("fn push_filters_0<'a>("), "{contents}");
+    assert!(contents.contains("Self::push_filters(req, &mut query);"));
+}